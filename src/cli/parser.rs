@@ -1,4 +1,6 @@
+use std::path::PathBuf;
 use clap::{Parser, Subcommand};
+use crate::core::domain::config::OutputFormat;
 
 #[derive(Parser)]
 #[command(name = "arsync")]
@@ -7,12 +9,40 @@ use clap::{Parser, Subcommand};
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// Never access the network; serve everything from the local cache
+    #[arg(long, global = true)]
+    pub offline: bool,
+
+    /// Treat skill validation warnings as errors
+    #[arg(long, global = true)]
+    pub strict: bool,
+
+    /// Output format for list, versions, cache list and config list (text or json)
+    #[arg(long, global = true, value_name = "FORMAT")]
+    pub output: Option<OutputFormat>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize a new skills.arsync manifest in the current directory
-    Init,
+    Init {
+        /// Project name (skips the prompt)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Project description (skips the prompt)
+        #[arg(long)]
+        description: Option<String>,
+
+        /// Directory skills are installed into (skips the prompt and agent detection)
+        #[arg(long, value_name = "DIR")]
+        install_dir: Option<String>,
+
+        /// Accept the defaults for everything not given as a flag
+        #[arg(long, short = 'y')]
+        yes: bool,
+    },
 
     /// Install skills from the manifest, or a specific skill if provided
     Install {
@@ -38,6 +68,14 @@ pub enum Commands {
         /// Specific source directory path within the repository
         #[arg(long)]
         path: Option<String>,
+
+        /// Install the skill only into this manifest target (repeatable; all targets by default)
+        #[arg(long = "target", value_name = "NAME")]
+        targets: Vec<String>,
+
+        /// Maximum number of skills fetched concurrently
+        #[arg(long, short = 'j')]
+        jobs: Option<usize>,
     },
 
     /// Uninstall a specific skill by its name/key
@@ -53,5 +91,123 @@ pub enum Commands {
     Update {
         /// Optional skill name to update
         skill_name: Option<String>,
+
+        /// Move the skill to a different ref (e.g. @v2.0.0, #main, or a commit hash)
+        #[arg(long, requires = "skill_name", conflicts_with_all = ["branch", "tag", "commit"])]
+        to: Option<String>,
+
+        /// Move the skill to a branch
+        #[arg(long, requires = "skill_name", conflicts_with_all = ["tag", "commit"])]
+        branch: Option<String>,
+
+        /// Move the skill to a tag
+        #[arg(long, requires = "skill_name", conflicts_with_all = ["branch", "commit"])]
+        tag: Option<String>,
+
+        /// Pin the skill to a full commit hash
+        #[arg(long, requires = "skill_name", conflicts_with_all = ["branch", "tag"])]
+        commit: Option<String>,
+
+        /// Maximum number of skills checked and fetched concurrently
+        #[arg(long, short = 'j')]
+        jobs: Option<usize>,
+
+        /// Keep the locked commit of skills whose folder did not change, instead of advancing it
+        #[arg(long)]
+        keep_unchanged: bool,
+    },
+
+    /// Download every dependency into the local cache without changing the project
+    Fetch {
+        /// Project directories or their skills.arsync files to fetch for (defaults to the current directory)
+        paths: Vec<PathBuf>,
+
+        /// Maximum number of repositories fetched concurrently
+        #[arg(long, short = 'j')]
+        jobs: Option<usize>,
     },
+
+    /// List the branches and tags available for a skill or source
+    Versions {
+        /// A skill name from the manifest, or a source (e.g. owner/repo/path)
+        target: String,
+    },
+
+    /// Write a catalog of the installed skills into AGENTS.md, CLAUDE.md or GEMINI.md
+    Catalog {
+        /// Instruction files to write (defaults to the manifest's catalog files, or the existing ones)
+        files: Vec<PathBuf>,
+    },
+
+    /// Inspect and manage the local repository cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommands,
+    },
+
+    /// Read and change settings in the user or project configuration file
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommands,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// List cached repositories with their size and when they were last used
+    List,
+
+    /// Remove one cached repository (owner/repo), or the whole cache
+    Clean {
+        repository: Option<String>,
+    },
+
+    /// Evict unused repositories and compact the remaining ones
+    Gc {
+        /// Evict repositories not used for this many days
+        #[arg(long, value_name = "DAYS")]
+        older_than: Option<u64>,
+
+        /// Evict least recently used repositories until the cache fits in this many megabytes
+        #[arg(long, value_name = "MB")]
+        max_size: Option<u64>,
+    },
+
+    /// Check the integrity of one cached repository (owner/repo), or of all of them
+    Verify {
+        repository: Option<String>,
+    },
+
+    /// Print the cache directory
+    Dir,
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Print the effective value of a setting
+    Get {
+        key: String,
+    },
+
+    /// Write a setting to the user configuration file
+    Set {
+        key: String,
+        value: String,
+
+        /// Write to the project's .arsync.toml instead
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Remove a setting from the user configuration file
+    Unset {
+        key: String,
+
+        /// Remove from the project's .arsync.toml instead
+        #[arg(long)]
+        project: bool,
+    },
+
+    /// Print every setting with its effective value and where it comes from
+    List,
 }
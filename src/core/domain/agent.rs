@@ -0,0 +1,46 @@
+/// Where an agent keeps its project files, and where and how it reads skills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentConvention {
+    /// Target name written to the manifest
+    pub name: &'static str,
+    /// Paths any one of which, present in a project, means the agent is used there
+    pub markers: &'static [&'static str],
+    /// Install target proposed for the agent
+    pub dir: &'static str,
+    /// Install target kind of `dir`
+    pub kind: &'static str,
+}
+
+/// Every agent arsync knows about, in the order they are proposed. New agents go here.
+pub const AGENTS: &[AgentConvention] = &[
+    AgentConvention { name: "claude", markers: &[".claude"], dir: ".claude/skills", kind: "skills" },
+    AgentConvention { name: "cursor", markers: &[".cursor"], dir: ".cursor/rules", kind: "cursor" },
+    AgentConvention { name: "gemini", markers: &[".gemini"], dir: ".gemini/antigravity/skills", kind: "skills" },
+    AgentConvention { name: "codex", markers: &[".codex"], dir: ".codex/skills", kind: "skills" },
+    AgentConvention { name: "copilot", markers: &[".github/copilot-instructions.md", ".github/instructions"], dir: ".github/instructions", kind: "copilot" },
+];
+
+/// The agents with a marker that `exists` reports as present.
+pub fn detect_agents(exists: impl Fn(&str) -> bool) -> Vec<&'static AgentConvention> {
+    AGENTS.iter().filter(|agent| agent.markers.iter().any(|marker| exists(marker))).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_agents_keeps_table_order() {
+        let detected = detect_agents(|marker| marker == ".github/instructions" || marker == ".claude");
+        let names: Vec<&str> = detected.iter().map(|agent| agent.name).collect();
+        assert_eq!(names, vec!["claude", "copilot"]);
+        assert!(detect_agents(|_| false).is_empty());
+    }
+
+    #[test]
+    fn test_github_directory_alone_is_not_copilot() {
+        assert!(detect_agents(|marker| marker == ".github").is_empty());
+        let detected = detect_agents(|marker| marker == ".github/copilot-instructions.md");
+        assert_eq!(detected.iter().map(|agent| agent.name).collect::<Vec<_>>(), vec!["copilot"]);
+    }
+}
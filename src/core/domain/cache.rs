@@ -0,0 +1,53 @@
+/// A repository held in the local git cache.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedRepository {
+    /// "owner/repo"
+    pub repository: String,
+    /// Bytes on disk
+    pub size: u64,
+    /// Unix time (seconds) of the last install, update or fetch that used it
+    pub last_used: u64,
+}
+
+/// Picks the repositories to evict: everything unused for longer than `max_age` seconds,
+/// then the least recently used ones until the rest fits in `max_size` bytes.
+pub fn select_evictions(repos: &[CachedRepository], now: u64, max_age: Option<u64>, max_size: Option<u64>) -> Vec<String> {
+    let mut by_age: Vec<&CachedRepository> = repos.iter().collect();
+    by_age.sort_by_key(|r| r.last_used);
+
+    let mut evicted = Vec::new();
+    let mut total: u64 = repos.iter().map(|r| r.size).sum();
+    for repo in by_age {
+        let too_old = max_age.is_some_and(|age| now.saturating_sub(repo.last_used) > age);
+        let too_big = max_size.is_some_and(|size| total > size);
+        if too_old || too_big {
+            total -= repo.size;
+            evicted.push(repo.repository.clone());
+        }
+    }
+    evicted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str, size: u64, last_used: u64) -> CachedRepository {
+        CachedRepository { repository: name.to_string(), size, last_used }
+    }
+
+    #[test]
+    fn test_evicts_by_age() {
+        let repos = vec![repo("a/old", 10, 100), repo("a/new", 10, 900)];
+        assert_eq!(select_evictions(&repos, 1000, Some(500), None), vec!["a/old"]);
+        assert!(select_evictions(&repos, 1000, None, None).is_empty());
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used_until_within_size() {
+        let repos = vec![repo("a/mid", 30, 500), repo("a/new", 30, 900), repo("a/old", 30, 100)];
+        assert_eq!(select_evictions(&repos, 1000, None, Some(60)), vec!["a/old"]);
+        assert_eq!(select_evictions(&repos, 1000, None, Some(59)), vec!["a/old", "a/mid"]);
+        assert!(select_evictions(&repos, 1000, None, Some(90)).is_empty());
+    }
+}
@@ -0,0 +1,99 @@
+use serde::Deserialize;
+use crate::core::domain::frontmatter::parse_frontmatter;
+
+pub const CATALOG_START: &str = "<!-- arsync:catalog:start -->";
+pub const CATALOG_END: &str = "<!-- arsync:catalog:end -->";
+
+/// Instruction files the catalog goes into by default, for agents that read one of them
+/// but do not discover skill folders on their own.
+pub const CATALOG_FILES: [&str; 3] = ["AGENTS.md", "CLAUDE.md", "GEMINI.md"];
+
+/// One installed skill, as listed in the catalog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogEntry {
+    pub name: String,
+    pub description: String,
+    /// Path of the skill's `SKILL.md`, relative to the project
+    pub path: String,
+}
+
+#[derive(Default, Deserialize)]
+struct Described {
+    description: Option<String>,
+}
+
+/// The description from a `SKILL.md` frontmatter, on a single line.
+pub fn skill_description(skill_md: &str) -> Option<String> {
+    let description = parse_frontmatter::<Described>(skill_md).ok()?.0.description?;
+    Some(description.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// The catalog section, markers included.
+pub fn render_catalog(entries: &[CatalogEntry]) -> String {
+    let mut out = format!("{}\n## Skills\n\n", CATALOG_START);
+    if entries.is_empty() {
+        out.push_str("No skills are installed.\n");
+    } else {
+        out.push_str("These skills are installed in this project. Before a task that matches a skill's description, read its `SKILL.md` and follow it.\n\n");
+        for entry in entries {
+            if entry.description.is_empty() {
+                out.push_str(&format!("- **{}** (`{}`)\n", entry.name, entry.path));
+            } else {
+                out.push_str(&format!("- **{}** (`{}`): {}\n", entry.name, entry.path, entry.description));
+            }
+        }
+    }
+    out.push_str(CATALOG_END);
+    out.push('\n');
+    out
+}
+
+/// Replaces the catalog section of `document` with `section`, or appends it when there is none.
+/// Everything outside the markers is left as it was.
+pub fn splice_catalog(document: &str, section: &str) -> String {
+    if let Some(start) = document.find(CATALOG_START) {
+        if let Some(end) = document[start..].find(CATALOG_END).map(|i| start + i + CATALOG_END.len()) {
+            let rest = document[end..].strip_prefix('\n').unwrap_or(&document[end..]);
+            return format!("{}{}{}", &document[..start], section, rest);
+        }
+    }
+    if document.trim().is_empty() {
+        return section.to_string();
+    }
+    let separator = if document.ends_with("\n\n") { "" } else if document.ends_with('\n') { "\n" } else { "\n\n" };
+    format!("{}{}{}", document, separator, section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, description: &str) -> CatalogEntry {
+        CatalogEntry { name: name.to_string(), description: description.to_string(), path: format!(".claude/skills/{}/SKILL.md", name) }
+    }
+
+    #[test]
+    fn test_skill_description() {
+        assert_eq!(skill_description("---\nname: pdf\ndescription: >\n  Work with\n  PDFs.\n---\nBody"), Some("Work with PDFs.".to_string()));
+        assert_eq!(skill_description("---\nname: pdf\n---\n"), None);
+        assert_eq!(skill_description("# Body only"), None);
+    }
+
+    #[test]
+    fn test_splice_appends_then_replaces_only_the_section() {
+        let document = "# Project\n\nHand-written notes.\n";
+        let first = splice_catalog(document, &render_catalog(&[entry("pdf", "Work with PDFs.")]));
+        assert!(first.starts_with("# Project\n\nHand-written notes.\n\n<!-- arsync:catalog:start -->\n"));
+        assert!(first.contains("- **pdf** (`.claude/skills/pdf/SKILL.md`): Work with PDFs.\n"));
+
+        let edited = format!("{}\n## More notes\n", first);
+        let second = splice_catalog(&edited, &render_catalog(&[entry("docx", "")]));
+        assert!(!second.contains("pdf"));
+        assert!(second.contains("- **docx** (`.claude/skills/docx/SKILL.md`)\n<!-- arsync:catalog:end -->\n"));
+        assert!(second.starts_with("# Project\n\nHand-written notes.\n\n"));
+        assert!(second.ends_with("<!-- arsync:catalog:end -->\n\n## More notes\n"));
+
+        // Rendering the same catalog again changes nothing
+        assert_eq!(splice_catalog(&second, &render_catalog(&[entry("docx", "")])), second);
+    }
+}
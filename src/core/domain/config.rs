@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+use crate::core::domain::error::AppError;
+use crate::core::domain::mirror::UrlRules;
+
+pub const DEFAULT_INSTALL_DIR: &str = ".gemini/antigravity/skills";
+pub const DEFAULT_HOST: &str = "github.com";
+
+/// Every supported setting, in display order.
+pub const KEYS: [&str; 8] = ["install-dir", "host", "jobs", "offline", "strict", "output", "timeout", "retries"];
+
+/// Settings that hold one value per name, set as `<table>.<name>` (e.g. `token.github.com`).
+pub const TABLES: [&str; 3] = ["token", "rewrite", "mirrors"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl FromStr for OutputFormat {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(AppError::Config(format!("Invalid output format '{}': expected text or json", s))),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// One layer of configuration (user file, project file, environment or command line).
+/// Unset values fall through to the layer below.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Settings {
+    /// Install directory suggested by `arsync init`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_dir: Option<String>,
+    /// Git host that `owner/repo` sources are fetched from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    /// Concurrent fetches for install, update and fetch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jobs: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offline: Option<bool>,
+    /// Treat skill validation warnings as errors
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputFormat>,
+    /// Seconds a network git command may run before it is killed (0 for no limit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// How often a network git command is retried after a transient failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+    /// Access tokens for private repositories, per git host (`token.<host>` keys)
+    #[serde(default, rename = "token", alias = "tokens", skip_serializing_if = "BTreeMap::is_empty")]
+    pub tokens: BTreeMap<String, String>,
+    /// URL prefixes fetched from somewhere else, like git's `insteadOf` (`rewrite.<prefix>` keys)
+    #[serde(default, rename = "rewrite", skip_serializing_if = "BTreeMap::is_empty")]
+    pub rewrites: BTreeMap<String, String>,
+    /// Mirror base URLs per host, tried in order when the host fails (`mirrors.<host>` keys, comma-separated)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mirrors: BTreeMap<String, Vec<String>>,
+}
+
+impl Settings {
+    /// Reads `ARSYNC_<KEY>` variables (e.g. `ARSYNC_INSTALL_DIR`); empty variables are ignored.
+    pub fn from_env(var: impl Fn(&str) -> Option<String>) -> Result<Self, AppError> {
+        let mut settings = Settings::default();
+        for key in KEYS {
+            let name = format!("ARSYNC_{}", key.to_uppercase().replace('-', "_"));
+            if let Some(value) = var(&name).filter(|v| !v.is_empty()) {
+                settings.set(key, &value).map_err(|e| match e {
+                    AppError::Config(msg) => AppError::Config(format!("{}: {}", name, msg)),
+                    other => other,
+                })?;
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Values set in `higher` win over the ones in `self`.
+    pub fn overridden_by(self, higher: Settings) -> Settings {
+        let mut tokens = self.tokens;
+        tokens.extend(higher.tokens);
+        let mut rewrites = self.rewrites;
+        rewrites.extend(higher.rewrites);
+        let mut mirrors = self.mirrors;
+        mirrors.extend(higher.mirrors);
+        Settings {
+            install_dir: higher.install_dir.or(self.install_dir),
+            host: higher.host.or(self.host),
+            jobs: higher.jobs.or(self.jobs),
+            offline: higher.offline.or(self.offline),
+            strict: higher.strict.or(self.strict),
+            output: higher.output.or(self.output),
+            timeout: higher.timeout.or(self.timeout),
+            retries: higher.retries.or(self.retries),
+            tokens,
+            rewrites,
+            mirrors,
+        }
+    }
+
+    pub fn url_rules(&self) -> UrlRules {
+        UrlRules { rewrites: self.rewrites.clone(), mirrors: self.mirrors.clone() }
+    }
+
+    /// The `<table>.<name>` keys that are set, in display order.
+    pub fn table_keys(&self) -> Vec<String> {
+        let names = |table: &str, keys: Vec<&String>| keys.into_iter().map(|k| format!("{}.{}", table, k)).collect::<Vec<_>>();
+        [names("token", self.tokens.keys().collect()), names("rewrite", self.rewrites.keys().collect()), names("mirrors", self.mirrors.keys().collect())].concat()
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<String>, AppError> {
+        if let Some((table, name)) = table_key(key)? {
+            return Ok(match table {
+                "token" => self.tokens.get(name).cloned(),
+                "rewrite" => self.rewrites.get(name).cloned(),
+                _ => self.mirrors.get(name).map(|urls| urls.join(",")),
+            });
+        }
+        Ok(match key {
+            "install-dir" => self.install_dir.clone(),
+            "host" => self.host.clone(),
+            "jobs" => self.jobs.map(|v| v.to_string()),
+            "offline" => self.offline.map(|v| v.to_string()),
+            "strict" => self.strict.map(|v| v.to_string()),
+            "output" => self.output.map(|v| v.to_string()),
+            "timeout" => self.timeout.map(|v| v.to_string()),
+            "retries" => self.retries.map(|v| v.to_string()),
+            _ => return Err(unknown_key(key)),
+        })
+    }
+
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), AppError> {
+        if let Some((table, name)) = table_key(key)? {
+            match table {
+                "token" => { self.tokens.insert(name.to_string(), value.to_string()); }
+                "rewrite" => { self.rewrites.insert(name.to_string(), value.to_string()); }
+                _ => {
+                    let urls = value.split(',').map(|u| u.trim().trim_end_matches('/').to_string()).filter(|u| !u.is_empty()).collect();
+                    self.mirrors.insert(name.to_string(), urls);
+                }
+            }
+            return Ok(());
+        }
+        match key {
+            "install-dir" => self.install_dir = Some(value.to_string()),
+            "host" => self.host = Some(value.trim_end_matches('/').to_string()),
+            "jobs" => self.jobs = Some(match value.parse() {
+                Ok(jobs) if jobs > 0 => jobs,
+                _ => return Err(AppError::Config(format!("Invalid jobs '{}': expected a positive number", value))),
+            }),
+            "offline" => self.offline = Some(parse_bool(value)?),
+            "strict" => self.strict = Some(parse_bool(value)?),
+            "output" => self.output = Some(value.parse()?),
+            "timeout" => self.timeout = Some(value.parse().map_err(|_| {
+                AppError::Config(format!("Invalid timeout '{}': expected a number of seconds", value))
+            })?),
+            "retries" => self.retries = Some(value.parse().map_err(|_| {
+                AppError::Config(format!("Invalid retries '{}': expected a number", value))
+            })?),
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+
+    pub fn unset(&mut self, key: &str) -> Result<(), AppError> {
+        if let Some((table, name)) = table_key(key)? {
+            match table {
+                "token" => { self.tokens.remove(name); }
+                "rewrite" => { self.rewrites.remove(name); }
+                _ => { self.mirrors.remove(name); }
+            }
+            return Ok(());
+        }
+        match key {
+            "install-dir" => self.install_dir = None,
+            "host" => self.host = None,
+            "jobs" => self.jobs = None,
+            "offline" => self.offline = None,
+            "strict" => self.strict = None,
+            "output" => self.output = None,
+            "timeout" => self.timeout = None,
+            "retries" => self.retries = None,
+            _ => return Err(unknown_key(key)),
+        }
+        Ok(())
+    }
+}
+
+/// Splits a `<table>.<name>` key; `None` for plain keys.
+fn table_key(key: &str) -> Result<Option<(&str, &str)>, AppError> {
+    match key.split_once('.') {
+        Some((table, "")) if TABLES.contains(&table) => Err(AppError::Config(format!("Missing name in '{}.<name>'", table))),
+        Some((table, name)) if TABLES.contains(&table) => Ok(Some((table, name))),
+        _ => Ok(None),
+    }
+}
+
+fn unknown_key(key: &str) -> AppError {
+    AppError::Config(format!(
+        "Unknown setting '{}' (known settings: {}, token.<host>, rewrite.<url prefix>, mirrors.<host>)",
+        key, KEYS.join(", ")
+    ))
+}
+
+/// The environment variable holding the token for `host`, e.g. `ARSYNC_TOKEN_GITHUB_COM`.
+pub fn token_env_var(host: &str) -> String {
+    let name: String = host.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' }).collect();
+    format!("ARSYNC_TOKEN_{}", name)
+}
+
+/// The access token for `host`: `ARSYNC_TOKEN_<HOST>`, then `GITHUB_TOKEN` for github.com, then the configured `token.<host>`.
+pub fn token_for(host: &str, var: impl Fn(&str) -> Option<String>, configured: &BTreeMap<String, String>) -> Option<String> {
+    let non_empty = |name: &str| var(name).filter(|v| !v.is_empty());
+    non_empty(&token_env_var(host))
+        .or_else(|| if host == DEFAULT_HOST { non_empty("GITHUB_TOKEN") } else { None })
+        .or_else(|| configured.get(host).cloned())
+}
+
+fn parse_bool(value: &str) -> Result<bool, AppError> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(AppError::Config(format!("Invalid value '{}': expected true or false", value))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_higher_layer_wins_per_setting() {
+        let user = Settings { jobs: Some(2), host: Some("git.example.com".to_string()), ..Default::default() };
+        let project = Settings { jobs: Some(4), ..Default::default() };
+        let merged = user.overridden_by(project);
+        assert_eq!(merged.jobs, Some(4));
+        assert_eq!(merged.host.as_deref(), Some("git.example.com"));
+    }
+
+    #[test]
+    fn test_set_parses_and_validates() {
+        let mut settings = Settings::default();
+        settings.set("offline", "yes").unwrap();
+        settings.set("output", "json").unwrap();
+        assert_eq!(settings.get("offline").unwrap().as_deref(), Some("true"));
+        assert_eq!(settings.output, Some(OutputFormat::Json));
+
+        assert!(settings.set("jobs", "0").is_err());
+        assert!(settings.set("output", "xml").is_err());
+        assert!(settings.set("colour", "always").is_err());
+    }
+
+    #[test]
+    fn test_token_lookup_order() {
+        let configured = BTreeMap::from([("github.com".to_string(), "from-config".to_string()), ("git.example.com".to_string(), "internal".to_string())]);
+        let env = |vars: &'static [(&'static str, &'static str)]| move |name: &str| vars.iter().find(|(k, _)| *k == name).map(|(_, v)| v.to_string());
+
+        assert_eq!(token_env_var("git.example-host.com"), "ARSYNC_TOKEN_GIT_EXAMPLE_HOST_COM");
+        assert_eq!(token_for("github.com", env(&[("GITHUB_TOKEN", "gh"), ("ARSYNC_TOKEN_GITHUB_COM", "specific")]), &configured).as_deref(), Some("specific"));
+        assert_eq!(token_for("github.com", env(&[("GITHUB_TOKEN", "gh")]), &configured).as_deref(), Some("gh"));
+        assert_eq!(token_for("git.example.com", env(&[("GITHUB_TOKEN", "gh")]), &configured).as_deref(), Some("internal"));
+        assert_eq!(token_for("gitlab.com", env(&[]), &configured), None);
+    }
+
+    #[test]
+    fn test_from_env() {
+        let settings = Settings::from_env(|name| match name {
+            "ARSYNC_INSTALL_DIR" => Some(".claude/skills".to_string()),
+            "ARSYNC_STRICT" => Some("1".to_string()),
+            "ARSYNC_JOBS" => Some(String::new()),
+            _ => None,
+        }).unwrap();
+        assert_eq!(settings, Settings { install_dir: Some(".claude/skills".to_string()), strict: Some(true), ..Default::default() });
+    }
+}
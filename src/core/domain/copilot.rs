@@ -0,0 +1,54 @@
+use serde::Deserialize;
+use crate::core::domain::error::AppError;
+use crate::core::domain::frontmatter::{parse_frontmatter, yaml_scalar};
+
+#[derive(Debug, Default, Deserialize)]
+struct InstructionsSource {
+    name: Option<String>,
+    description: Option<String>,
+}
+
+/// Renders a skill's `SKILL.md` as a Copilot instructions file (`.instructions.md`). The skill's
+/// description becomes the file's header, `apply_to` its `applyTo` glob (without one, Copilot only
+/// uses the file when it is attached by hand), and the body its content, followed by a pointer to
+/// `resources`, the folder holding the skill's other files.
+pub fn to_copilot_instructions(skill_md: &str, apply_to: Option<&str>, resources: &str) -> Result<String, AppError> {
+    let (source, body) = parse_frontmatter::<InstructionsSource>(skill_md)?;
+
+    let mut header = String::new();
+    if let Some(description) = source.description.or(source.name) {
+        let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+        header.push_str(&format!("description: {}\n", yaml_scalar(&description)));
+    }
+    if let Some(apply_to) = apply_to {
+        header.push_str(&format!("applyTo: {}\n", yaml_scalar(apply_to)));
+    }
+
+    Ok(format!(
+        "---\n{}---\n{}\n\nFiles these instructions refer to are in `{}/`.\n",
+        header,
+        body.trim(),
+        resources,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply_to_fixture() {
+        let instructions = to_copilot_instructions(
+            include_str!("../../../tests/fixtures/copilot/typescript/SKILL.md"),
+            Some("src/**/*.{ts,tsx}"),
+            ".github/instructions/typescript",
+        ).unwrap();
+        assert_eq!(instructions, include_str!("../../../tests/fixtures/copilot/typescript/typescript.instructions.md"));
+    }
+
+    #[test]
+    fn test_without_apply_to() {
+        let instructions = to_copilot_instructions("---\nname: notes\n---\n# Notes\n", None, ".github/instructions/notes").unwrap();
+        assert_eq!(instructions, "---\ndescription: \"notes\"\n---\n# Notes\n\nFiles these instructions refer to are in `.github/instructions/notes/`.\n");
+    }
+}
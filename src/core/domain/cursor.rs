@@ -0,0 +1,78 @@
+use serde::Deserialize;
+use crate::core::domain::error::AppError;
+use crate::core::domain::frontmatter::{parse_frontmatter, yaml_scalar};
+
+/// The `SKILL.md` frontmatter fields a Cursor rule is built from. `globs` and `alwaysApply`
+/// are not part of the skill format, but are honored when a skill sets them for Cursor.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RuleSource {
+    name: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    globs: Globs,
+    #[serde(default)]
+    always_apply: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(untagged)]
+enum Globs {
+    #[default]
+    None,
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Renders a skill's `SKILL.md` as a Cursor rule (`.mdc`). The skill's description tells Cursor
+/// when to pull the rule in; the body becomes the rule's content, followed by a pointer to
+/// `resources`, the folder holding the skill's other files (scripts, references).
+pub fn to_cursor_rule(skill_md: &str, resources: &str) -> Result<String, AppError> {
+    let (source, body) = parse_frontmatter::<RuleSource>(skill_md)?;
+    let description = source.description.or(source.name).unwrap_or_default();
+    let description = yaml_scalar(&description.split_whitespace().collect::<Vec<_>>().join(" "));
+    let globs = match source.globs {
+        Globs::None => String::new(),
+        Globs::One(glob) => glob,
+        Globs::Many(globs) => globs.join(","),
+    };
+
+    Ok(format!(
+        "---\ndescription: {}\nglobs: {}\nalwaysApply: {}\n---\n{}\n\nFiles this rule refers to are in `{}/`.\n",
+        description,
+        globs,
+        source.always_apply,
+        body.trim(),
+        resources,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_basic_skill_fixture() {
+        let rule = to_cursor_rule(include_str!("../../../tests/fixtures/cursor/basic/SKILL.md"), ".cursor/rules/pdf").unwrap();
+        assert_eq!(rule, include_str!("../../../tests/fixtures/cursor/basic/pdf.mdc"));
+    }
+
+    #[test]
+    fn test_globs_fixture() {
+        let rule = to_cursor_rule(include_str!("../../../tests/fixtures/cursor/globs/SKILL.md"), ".cursor/rules/rust-style").unwrap();
+        assert_eq!(rule, include_str!("../../../tests/fixtures/cursor/globs/rust-style.mdc"));
+    }
+
+    #[test]
+    fn test_description_with_colon_fixture() {
+        let rule = to_cursor_rule(include_str!("../../../tests/fixtures/cursor/colon/SKILL.md"), ".cursor/rules/release").unwrap();
+        assert_eq!(rule, include_str!("../../../tests/fixtures/cursor/colon/release.mdc"));
+    }
+
+    #[test]
+    fn test_skill_without_frontmatter_keeps_body() {
+        let rule = to_cursor_rule("# Notes\n", ".cursor/rules/notes").unwrap();
+        assert!(rule.starts_with("---\ndescription: \"\"\nglobs: \nalwaysApply: false\n---\n# Notes\n"));
+        assert!(to_cursor_rule("---\nname: [unclosed\n---\n", "x").is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+use crate::core::domain::skill::SkillSource;
+
+/// How `update` is allowed to move a dependency's locked commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdatePolicy {
+    /// Follow the branch/ref written in the source (default).
+    #[default]
+    Branch,
+    /// Only move to the newest release tag published on the remote.
+    Tags,
+    /// Never updated by a bulk `update`; only when named explicitly.
+    Manual,
+}
+
+impl UpdatePolicy {
+    pub fn is_default(&self) -> bool {
+        *self == UpdatePolicy::default()
+    }
+}
+
+impl std::fmt::Display for UpdatePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            UpdatePolicy::Branch => "branch",
+            UpdatePolicy::Tags => "tags",
+            UpdatePolicy::Manual => "manual",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+/// A single entry of the manifest's `dependencies` map.
+///
+/// Serialized as a plain source string when every option is at its default,
+/// so existing manifests keep their shape; otherwise as an object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "DependencySpec", into = "DependencySpec")]
+pub struct Dependency {
+    pub source: SkillSource,
+    pub update: UpdatePolicy,
+    /// Names of the manifest targets this skill is installed into; all of them when `None`.
+    pub targets: Option<Vec<String>>,
+    /// Glob of the files the skill applies to, for agents that scope instructions by path (Copilot's `applyTo`).
+    pub apply_to: Option<String>,
+}
+
+impl Dependency {
+    pub fn new(source: SkillSource) -> Self {
+        Self { source, update: UpdatePolicy::default(), targets: None, apply_to: None }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum DependencySpec {
+    Short(SkillSource),
+    Detailed {
+        source: SkillSource,
+        #[serde(default, skip_serializing_if = "UpdatePolicy::is_default")]
+        update: UpdatePolicy,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        targets: Option<Vec<String>>,
+        #[serde(rename = "apply-to", default, skip_serializing_if = "Option::is_none")]
+        apply_to: Option<String>,
+    },
+}
+
+impl From<DependencySpec> for Dependency {
+    fn from(spec: DependencySpec) -> Self {
+        match spec {
+            DependencySpec::Short(source) => Dependency::new(source),
+            DependencySpec::Detailed { source, update, targets, apply_to } => Dependency { source, update, targets, apply_to },
+        }
+    }
+}
+
+impl From<Dependency> for DependencySpec {
+    fn from(dep: Dependency) -> Self {
+        if dep.update.is_default() && dep.targets.is_none() && dep.apply_to.is_none() {
+            DependencySpec::Short(dep.source)
+        } else {
+            DependencySpec::Detailed { source: dep.source, update: dep.update, targets: dep.targets, apply_to: dep.apply_to }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_form_round_trip() {
+        let dep: Dependency = serde_json::from_str("\"owner/repo/skill#main\"").unwrap();
+        assert_eq!(dep.source.as_str(), "owner/repo/skill#main");
+        assert_eq!(dep.update, UpdatePolicy::Branch);
+        assert_eq!(serde_json::to_string(&dep).unwrap(), "\"owner/repo/skill#main\"");
+    }
+
+    #[test]
+    fn test_detailed_form_round_trip() {
+        let dep: Dependency = serde_json::from_str(r#"{"source":"owner/repo","update":"tags"}"#).unwrap();
+        assert_eq!(dep.update, UpdatePolicy::Tags);
+        assert_eq!(serde_json::to_string(&dep).unwrap(), r#"{"source":"owner/repo","update":"tags"}"#);
+    }
+
+    #[test]
+    fn test_detailed_form_with_default_policy_collapses() {
+        let dep: Dependency = serde_json::from_str(r#"{"source":"owner/repo"}"#).unwrap();
+        assert_eq!(serde_json::to_string(&dep).unwrap(), "\"owner/repo\"");
+    }
+
+    #[test]
+    fn test_target_filter_round_trip() {
+        let dep: Dependency = serde_json::from_str(r#"{"source":"owner/repo","targets":["claude"]}"#).unwrap();
+        assert_eq!(dep.targets, Some(vec!["claude".to_string()]));
+        assert_eq!(serde_json::to_string(&dep).unwrap(), r#"{"source":"owner/repo","targets":["claude"]}"#);
+
+        let dep: Dependency = serde_json::from_str(r#"{"source":"owner/repo","apply-to":"**/*.ts"}"#).unwrap();
+        assert_eq!(dep.apply_to.as_deref(), Some("**/*.ts"));
+        assert_eq!(serde_json::to_string(&dep).unwrap(), r#"{"source":"owner/repo","apply-to":"**/*.ts"}"#);
+    }
+
+    #[test]
+    fn test_unknown_policy_is_rejected() {
+        assert!(serde_json::from_str::<Dependency>(r#"{"source":"owner/repo","update":"nightly"}"#).is_err());
+    }
+}
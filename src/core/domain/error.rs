@@ -10,6 +10,27 @@ pub enum AppError {
     Io(#[from] std::io::Error),
     #[error("serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
+    #[error("config error: {0}")]
+    Config(String),
+    #[error("authentication failed: {0}")]
+    Auth(String),
     #[error("system error: {0}")]
     System(String),
 }
+
+/// Repository-level failures are reported for every skill of the repository, so errors are
+/// duplicated with their variant (and e.g. the auth hint) intact. I/O and serialization
+/// errors keep their kind and message.
+impl Clone for AppError {
+    fn clone(&self) -> Self {
+        match self {
+            AppError::Manifest(msg) => AppError::Manifest(msg.clone()),
+            AppError::Skill(msg) => AppError::Skill(msg.clone()),
+            AppError::Io(e) => AppError::Io(std::io::Error::new(e.kind(), e.to_string())),
+            AppError::Serialization(e) => AppError::Serialization(serde_json::Error::io(std::io::Error::other(e.to_string()))),
+            AppError::Config(msg) => AppError::Config(msg.clone()),
+            AppError::Auth(msg) => AppError::Auth(msg.clone()),
+            AppError::System(msg) => AppError::System(msg.clone()),
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use serde::de::DeserializeOwned;
+use crate::core::domain::error::AppError;
+
+/// Splits a markdown document with YAML frontmatter (`---` lines around it) into the
+/// frontmatter and the body. `None` when the document does not start with frontmatter.
+pub fn split_frontmatter(content: &str) -> Option<(&str, &str)> {
+    let rest = content.strip_prefix("---")?;
+    let rest = rest.strip_prefix("\r\n").or_else(|| rest.strip_prefix('\n'))?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Some((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Reads the frontmatter fields of a `SKILL.md` into `T` and returns them with the body.
+/// A document without frontmatter has only a body.
+pub fn parse_frontmatter<T: DeserializeOwned + Default>(skill_md: &str) -> Result<(T, &str), AppError> {
+    match split_frontmatter(skill_md) {
+        Some((frontmatter, body)) => {
+            let fields = serde_yaml::from_str(frontmatter)
+                .map_err(|e| AppError::Skill(format!("Invalid SKILL.md frontmatter: {}", e)))?;
+            Ok((fields, body))
+        }
+        None => Ok((T::default(), skill_md)),
+    }
+}
+
+/// `value` as a YAML scalar for generated frontmatter. JSON strings are valid double-quoted
+/// YAML scalars, so values with `:` or `#` stay intact.
+pub fn yaml_scalar(value: &str) -> String {
+    serde_json::Value::String(value.to_string()).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_frontmatter() {
+        assert_eq!(split_frontmatter("---\nname: a\n---\n# Body\n"), Some(("name: a\n", "# Body\n")));
+        assert_eq!(split_frontmatter("---\r\nname: a\r\n---\r\nBody"), Some(("name: a\r\n", "Body")));
+        assert_eq!(split_frontmatter("---\ndescription: uses --- inline\n---\n"), Some(("description: uses --- inline\n", "")));
+        assert_eq!(split_frontmatter("# No frontmatter\n"), None);
+        assert_eq!(split_frontmatter("---\nname: a\n"), None);
+    }
+
+    #[test]
+    fn test_yaml_scalar() {
+        assert_eq!(yaml_scalar("Release: tag #ship"), "\"Release: tag #ship\"");
+        assert_eq!(yaml_scalar("say \"hi\""), "\"say \\\"hi\\\"\"");
+    }
+}
@@ -10,7 +10,54 @@ pub struct Lockfile {
     #[serde(rename = "install-dir", skip_serializing_if = "Option::is_none")]
     pub install_dir: Option<PathBuf>,
     #[serde(default)]
-    pub dependencies: HashMap<String, String>,
+    pub dependencies: HashMap<String, LockEntry>,
+}
+
+/// What a skill is locked to: the commit, and the git tree hash of the skill's folder at that commit.
+///
+/// The tree hash identifies the skill's content independently of the commit, so a
+/// monorepo commit that does not touch the folder is recognized as "no content change".
+/// Older lockfiles stored only the commit as a plain string; both forms are accepted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "LockEntrySpec", into = "LockEntrySpec")]
+pub struct LockEntry {
+    pub commit: String,
+    pub tree: Option<String>,
+}
+
+impl LockEntry {
+    pub fn new(commit: String, tree: String) -> Self {
+        Self { commit, tree: Some(tree) }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum LockEntrySpec {
+    Commit(String),
+    Detailed {
+        commit: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tree: Option<String>,
+    },
+}
+
+impl From<LockEntrySpec> for LockEntry {
+    fn from(spec: LockEntrySpec) -> Self {
+        match spec {
+            LockEntrySpec::Commit(commit) => LockEntry { commit, tree: None },
+            LockEntrySpec::Detailed { commit, tree } => LockEntry { commit, tree },
+        }
+    }
+}
+
+impl From<LockEntry> for LockEntrySpec {
+    fn from(entry: LockEntry) -> Self {
+        match entry.tree {
+            None => LockEntrySpec::Commit(entry.commit),
+            tree => LockEntrySpec::Detailed { commit: entry.commit, tree },
+        }
+    }
 }
 
 impl Lockfile {
@@ -23,15 +70,39 @@ impl Lockfile {
         }
     }
 
-    pub fn set_commit(&mut self, key: String, commit_hash: String) {
-        self.dependencies.insert(key, commit_hash);
+    pub fn set_entry(&mut self, key: String, entry: LockEntry) {
+        self.dependencies.insert(key, entry);
     }
 
     pub fn remove_commit(&mut self, key: &str) {
         self.dependencies.remove(key);
     }
 
-    pub fn get_commit(&self, key: &str) -> Option<&String> {
+    pub fn get_entry(&self, key: &str) -> Option<&LockEntry> {
         self.dependencies.get(key)
     }
+
+    pub fn get_commit(&self, key: &str) -> Option<&String> {
+        self.dependencies.get(key).map(|e| &e.commit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_commit_string_is_accepted() {
+        let entry: LockEntry = serde_json::from_str("\"abc123\"").unwrap();
+        assert_eq!(entry, LockEntry { commit: "abc123".to_string(), tree: None });
+        assert_eq!(serde_json::to_string(&entry).unwrap(), "\"abc123\"");
+    }
+
+    #[test]
+    fn test_entry_with_tree_round_trip() {
+        let entry = LockEntry::new("abc123".to_string(), "def456".to_string());
+        let json = serde_json::to_string(&entry).unwrap();
+        assert_eq!(json, r#"{"commit":"abc123","tree":"def456"}"#);
+        assert_eq!(serde_json::from_str::<LockEntry>(&json).unwrap(), entry);
+    }
 }
@@ -1,7 +1,9 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
-use crate::core::domain::skill::{SkillName, SkillSource};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use crate::core::domain::skill::SkillName;
+use crate::core::domain::dependency::Dependency;
+use crate::core::domain::error::AppError;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Manifest {
@@ -9,8 +11,102 @@ pub struct Manifest {
     pub description: String,
     #[serde(rename = "install-dir", skip_serializing_if = "Option::is_none")]
     pub install_dir: Option<PathBuf>,
+    #[serde(rename = "install-mode", default, skip_serializing_if = "InstallMode::is_copy")]
+    pub install_mode: InstallMode,
+    /// Install directories per agent (e.g. `"claude": ".claude/skills"`). When present, every skill
+    /// is installed into each of them and `install-dir` is ignored.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub targets: BTreeMap<String, Target>,
+    /// Instruction files (e.g. `AGENTS.md`) whose skills catalog is refreshed after install, update and uninstall.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub catalog: Vec<PathBuf>,
     #[serde(default)]
-    pub dependencies: HashMap<String, SkillSource>,
+    pub dependencies: HashMap<String, Dependency>,
+}
+
+/// How skill folders are placed into the install directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InstallMode {
+    /// A private copy per project (default).
+    #[default]
+    Copy,
+    /// A symlink to the shared content in the global store.
+    Symlink,
+    /// A directory whose files are hard links into the global store.
+    Hardlink,
+}
+
+impl InstallMode {
+    pub fn is_copy(&self) -> bool {
+        *self == InstallMode::Copy
+    }
+}
+
+/// The kind of the targets that simply hold skill folders with a `SKILL.md`, and of `install-dir`.
+pub const DEFAULT_TARGET_KIND: &str = "skills";
+
+/// One of the manifest's install targets. `kind` names the install target adapter that
+/// turns skills into what the agent reads there (see `TargetAdapters`).
+///
+/// Serialized as a plain path for the default kind, otherwise as `{ "path": ..., "kind": ... }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "TargetSpec", into = "TargetSpec")]
+pub struct Target {
+    pub path: PathBuf,
+    pub kind: String,
+}
+
+impl Target {
+    pub fn new(path: PathBuf, kind: &str) -> Self {
+        Self { path, kind: kind.to_string() }
+    }
+}
+
+/// Where a target of the manifest is, of which kind, and whether a given skill goes there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetDir<'m> {
+    pub path: PathBuf,
+    pub kind: &'m str,
+    pub selected: bool,
+}
+
+fn is_default_kind(kind: &String) -> bool {
+    kind == DEFAULT_TARGET_KIND
+}
+
+fn default_kind() -> String {
+    DEFAULT_TARGET_KIND.to_string()
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum TargetSpec {
+    Short(PathBuf),
+    Detailed {
+        path: PathBuf,
+        #[serde(default = "default_kind", skip_serializing_if = "is_default_kind")]
+        kind: String,
+    },
+}
+
+impl From<TargetSpec> for Target {
+    fn from(spec: TargetSpec) -> Self {
+        match spec {
+            TargetSpec::Short(path) => Target::new(path, DEFAULT_TARGET_KIND),
+            TargetSpec::Detailed { path, kind } => Target { path, kind },
+        }
+    }
+}
+
+impl From<Target> for TargetSpec {
+    fn from(target: Target) -> Self {
+        if is_default_kind(&target.kind) {
+            TargetSpec::Short(target.path)
+        } else {
+            TargetSpec::Detailed { path: target.path, kind: target.kind }
+        }
+    }
 }
 
 impl Manifest {
@@ -19,15 +115,120 @@ impl Manifest {
             name,
             description,
             install_dir: None,
+            install_mode: InstallMode::default(),
+            targets: BTreeMap::new(),
+            catalog: Vec::new(),
             dependencies: HashMap::new(),
         }
     }
 
-    pub fn add_dependency(&mut self, key: String, source: SkillSource) {
-        self.dependencies.insert(key, source);
+    pub fn add_dependency(&mut self, key: String, dependency: Dependency) {
+        self.dependencies.insert(key, dependency);
     }
 
     pub fn remove_dependency(&mut self, key: &str) {
         self.dependencies.remove(key);
     }
+
+    /// Where skill `key` is installed: `<target>/<key>` for each target it applies to, or
+    /// `<install-dir>/<key>` when there are no targets. Relative paths are resolved against `dir`.
+    pub fn install_paths(&self, dir: &Path, key: &str) -> Vec<PathBuf> {
+        self.target_dirs(dir, key).into_iter()
+            .filter(|target| target.selected)
+            .map(|target| target.path.join(key))
+            .collect()
+    }
+
+    /// Every target directory, marking those skill `key` is installed into. Without targets,
+    /// `install-dir` is the one target, of the default kind.
+    pub fn target_dirs(&self, dir: &Path, key: &str) -> Vec<TargetDir<'_>> {
+        if self.targets.is_empty() {
+            let base = self.install_dir.as_deref().unwrap_or(Path::new(""));
+            return vec![TargetDir { path: dir.join(base), kind: DEFAULT_TARGET_KIND, selected: true }];
+        }
+        let filter = self.dependencies.get(key).and_then(|d| d.targets.as_ref());
+        self.targets.iter()
+            .map(|(name, target)| TargetDir {
+                path: dir.join(&target.path),
+                kind: &target.kind,
+                selected: filter.is_none_or(|f| f.contains(name)),
+            })
+            .collect()
+    }
+
+    /// Every dependency's target filter must name known targets and leave at least one.
+    pub fn check_targets(&self) -> Result<(), AppError> {
+        let mut keys: Vec<&String> = self.dependencies.keys().collect();
+        keys.sort();
+        for key in keys {
+            let Some(filter) = &self.dependencies[key].targets else { continue };
+            if self.targets.is_empty() {
+                return Err(AppError::Manifest(format!("'{}' selects targets, but the manifest defines none", key)));
+            }
+            if let Some(unknown) = filter.iter().find(|t| !self.targets.contains_key(*t)) {
+                return Err(AppError::Manifest(format!(
+                    "'{}' selects unknown target '{}' (targets: {})",
+                    key, unknown, self.targets.keys().cloned().collect::<Vec<_>>().join(", ")
+                )));
+            }
+            if filter.is_empty() {
+                return Err(AppError::Manifest(format!("'{}' selects no targets", key)));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(json: &str) -> Manifest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_install_paths_without_targets_use_install_dir() {
+        let m = manifest(r#"{"name":"p","description":"","install-dir":"skills","dependencies":{"a":"o/r/a"}}"#);
+        assert_eq!(m.install_paths(Path::new("/proj"), "a"), vec![PathBuf::from("/proj/skills/a")]);
+    }
+
+    #[test]
+    fn test_install_paths_fan_out_and_filter() {
+        let m = manifest(r#"{
+            "name": "p", "description": "",
+            "targets": {"claude": ".claude/skills", "gemini": ".gemini/skills"},
+            "dependencies": {"a": "o/r/a", "b": {"source": "o/r/b", "targets": ["gemini"]}}
+        }"#);
+        assert_eq!(m.install_paths(Path::new("/proj"), "a"), vec![PathBuf::from("/proj/.claude/skills/a"), PathBuf::from("/proj/.gemini/skills/a")]);
+        assert_eq!(m.install_paths(Path::new("/proj"), "b"), vec![PathBuf::from("/proj/.gemini/skills/b")]);
+        assert!(!m.target_dirs(Path::new("/proj"), "b")[0].selected);
+        assert!(m.check_targets().is_ok());
+    }
+
+    #[test]
+    fn test_target_kinds() {
+        let m = manifest(r#"{
+            "name": "p", "description": "",
+            "targets": {"claude": ".claude/skills", "cursor": {"path": ".cursor/rules", "kind": "cursor"}},
+            "dependencies": {"a": "o/r/a"}
+        }"#);
+        let kinds: Vec<&str> = m.target_dirs(Path::new("/proj"), "a").iter().map(|t| t.kind).collect();
+        assert_eq!(kinds, vec!["skills", "cursor"]);
+        assert_eq!(m.install_paths(Path::new("/proj"), "a"), vec![PathBuf::from("/proj/.claude/skills/a"), PathBuf::from("/proj/.cursor/rules/a")]);
+
+        let json = serde_json::to_value(&m).unwrap();
+        assert_eq!(json["targets"]["claude"], ".claude/skills");
+        assert_eq!(json["targets"]["cursor"]["kind"], "cursor");
+    }
+
+    #[test]
+    fn test_unknown_target_is_rejected() {
+        let m = manifest(r#"{
+            "name": "p", "description": "",
+            "targets": {"claude": ".claude/skills"},
+            "dependencies": {"b": {"source": "o/r/b", "targets": ["cursor"]}}
+        }"#);
+        assert!(m.check_targets().is_err());
+    }
 }
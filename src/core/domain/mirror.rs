@@ -0,0 +1,87 @@
+use std::collections::BTreeMap;
+
+/// Where repositories are actually fetched from, for networks that cannot reach the source host.
+/// The canonical `https://<host>/<owner>/<repo>.git` URL is what projects refer to; these rules only
+/// change where git connects.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UrlRules {
+    /// URLs starting with a key are fetched from the value instead, like git's `url.<base>.insteadOf`.
+    /// The longest matching prefix wins.
+    pub rewrites: BTreeMap<String, String>,
+    /// Base URLs per host, tried in order when the primary URL fails.
+    pub mirrors: BTreeMap<String, Vec<String>>,
+}
+
+impl UrlRules {
+    pub fn rewrite(&self, url: &str) -> String {
+        self.rewrites.iter()
+            .filter(|(prefix, _)| url.starts_with(prefix.as_str()))
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, replacement)| format!("{}{}", replacement, &url[prefix.len()..]))
+            .unwrap_or_else(|| url.to_string())
+    }
+
+    /// The URLs to try for `canonical`, in order: the rewritten URL, then each mirror of its host
+    /// (with the repository path appended, rewritten as well).
+    pub fn candidates(&self, canonical: &str) -> Vec<String> {
+        let mut urls = vec![self.rewrite(canonical)];
+        let (host, path) = split_url(canonical);
+        for base in self.mirrors.get(host).into_iter().flatten() {
+            let url = self.rewrite(&format!("{}/{}", base.trim_end_matches('/'), path));
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+}
+
+/// The host (and port) of a URL, without user info.
+pub fn host_of(url: &str) -> &str {
+    let authority = split_url(url).0;
+    authority.rsplit_once('@').map(|(_, host)| host).unwrap_or(authority)
+}
+
+/// Splits `scheme://authority/path` into the authority and the path.
+fn split_url(url: &str) -> (&str, &str) {
+    let rest = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    rest.split_once('/').unwrap_or((rest, ""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_longest_rewrite_prefix_wins() {
+        let rules = UrlRules {
+            rewrites: BTreeMap::from([
+                ("https://github.com/".to_string(), "https://git.internal/github/".to_string()),
+                ("https://github.com/acme/".to_string(), "https://git.internal/acme/".to_string()),
+            ]),
+            ..Default::default()
+        };
+        assert_eq!(rules.rewrite("https://github.com/o/r.git"), "https://git.internal/github/o/r.git");
+        assert_eq!(rules.rewrite("https://github.com/acme/r.git"), "https://git.internal/acme/r.git");
+        assert_eq!(rules.rewrite("https://gitlab.com/o/r.git"), "https://gitlab.com/o/r.git");
+    }
+
+    #[test]
+    fn test_candidates_append_mirrors_in_order() {
+        let rules = UrlRules {
+            mirrors: BTreeMap::from([("github.com".to_string(), vec!["https://m1.internal/gh/".to_string(), "https://m2.internal".to_string()])]),
+            ..Default::default()
+        };
+        assert_eq!(rules.candidates("https://github.com/o/r.git"), vec![
+            "https://github.com/o/r.git",
+            "https://m1.internal/gh/o/r.git",
+            "https://m2.internal/o/r.git",
+        ]);
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of("https://github.com/owner/repo.git"), "github.com");
+        assert_eq!(host_of("https://user@git.example.com:8443/owner/repo.git"), "git.example.com:8443");
+    }
+}
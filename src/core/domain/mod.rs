@@ -3,3 +3,13 @@ pub mod manifest;
 pub mod skill;
 pub mod lockfile;
 pub mod validation;
+pub mod dependency;
+pub mod version;
+pub mod cache;
+pub mod config;
+pub mod mirror;
+pub mod agent;
+pub mod frontmatter;
+pub mod cursor;
+pub mod copilot;
+pub mod catalog;
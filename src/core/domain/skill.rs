@@ -37,4 +37,116 @@ impl SkillSource {
     pub fn as_str(&self) -> &str {
         &self.0
     }
+
+    /// Splits `owner/repo/path@ref` (or `#ref`) into its location and optional ref.
+    pub fn split_ref(&self) -> (&str, Option<&str>) {
+        if let Some(idx) = self.0.find('@') {
+            (&self.0[..idx], Some(&self.0[idx + 1..]))
+        } else if let Some(idx) = self.0.find('#') {
+            (&self.0[..idx], Some(&self.0[idx + 1..]))
+        } else {
+            (&self.0, None)
+        }
+    }
+
+    /// The `owner/repo` part of the source; skills sharing it share one cache and checkout.
+    pub fn repository(&self) -> &str {
+        let (location, _) = self.split_ref();
+        match location.match_indices('/').nth(1) {
+            Some((idx, _)) => &location[..idx],
+            None => location,
+        }
+    }
+
+    /// The directory inside the repository (empty for the repository root).
+    pub fn subpath(&self) -> &str {
+        let (location, _) = self.split_ref();
+        match location.match_indices('/').nth(1) {
+            Some((idx, _)) => &location[idx + 1..],
+            None => "",
+        }
+    }
+
+    /// Returns the same location pointed at a different ref.
+    pub fn with_ref(&self, source_ref: &SourceRef) -> Self {
+        let (location, _) = self.split_ref();
+        match source_ref {
+            SourceRef::Branch(b) => Self(format!("{}#{}", location, b)),
+            SourceRef::Tag(r) | SourceRef::Commit(r) => Self(format!("{}@{}", location, r)),
+        }
+    }
+}
+
+/// An explicit ref a source can be pointed at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceRef {
+    Branch(String),
+    Tag(String),
+    Commit(String),
+}
+
+impl SourceRef {
+    /// Parses the ref part of the shorthand syntax: `#branch`, `@tag`, or a bare ref.
+    /// A bare full-length hex hash is taken as a commit, anything else as a tag.
+    pub fn parse(s: &str) -> Result<Self, AppError> {
+        let (ctor, name): (fn(String) -> Self, &str) = if let Some(b) = s.strip_prefix('#') {
+            (SourceRef::Branch, b)
+        } else if let Some(t) = s.strip_prefix('@') {
+            (SourceRef::Tag, t)
+        } else if is_commit_hash(s) {
+            (SourceRef::Commit, s)
+        } else {
+            (SourceRef::Tag, s)
+        };
+
+        if name.is_empty() {
+            return Err(AppError::Skill(format!("Invalid ref: '{}'", s)));
+        }
+        Ok(ctor(name.to_string()))
+    }
+}
+
+/// True for a full SHA-1 or SHA-256 object name.
+pub fn is_commit_hash(s: &str) -> bool {
+    (s.len() == 40 || s.len() == 64) && s.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_ref() {
+        assert_eq!(SkillSource::new("owner/repo/skill#main").split_ref(), ("owner/repo/skill", Some("main")));
+        assert_eq!(SkillSource::new("owner/repo@v1.0.0").split_ref(), ("owner/repo", Some("v1.0.0")));
+        assert_eq!(SkillSource::new("owner/repo").split_ref(), ("owner/repo", None));
+    }
+
+    #[test]
+    fn test_repository_and_subpath() {
+        let source = SkillSource::new("org/skills/skills/a#main");
+        assert_eq!(source.repository(), "org/skills");
+        assert_eq!(source.subpath(), "skills/a");
+
+        let root = SkillSource::new("org/skill@v1");
+        assert_eq!(root.repository(), "org/skill");
+        assert_eq!(root.subpath(), "");
+    }
+
+    #[test]
+    fn test_with_ref_replaces_existing_ref() {
+        let source = SkillSource::new("owner/repo/skill#main");
+        assert_eq!(source.with_ref(&SourceRef::Tag("v2.0.0".to_string())).as_str(), "owner/repo/skill@v2.0.0");
+        assert_eq!(source.with_ref(&SourceRef::Branch("dev".to_string())).as_str(), "owner/repo/skill#dev");
+    }
+
+    #[test]
+    fn test_source_ref_parse() {
+        let sha = "0123456789abcdef0123456789abcdef01234567";
+        assert_eq!(SourceRef::parse("#main").unwrap(), SourceRef::Branch("main".to_string()));
+        assert_eq!(SourceRef::parse("@v2.0.0").unwrap(), SourceRef::Tag("v2.0.0".to_string()));
+        assert_eq!(SourceRef::parse("v2.0.0").unwrap(), SourceRef::Tag("v2.0.0".to_string()));
+        assert_eq!(SourceRef::parse(sha).unwrap(), SourceRef::Commit(sha.to_string()));
+        assert!(SourceRef::parse("#").is_err());
+    }
 }
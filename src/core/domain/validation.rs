@@ -8,7 +8,8 @@ struct SkillFrontmatter {
     name: Option<String>,
 }
 
-pub fn validate_skill_soft(skill_dir: &Path, expected_dir_name: &str) {
+/// Prints a warning for each problem found; returns `true` when the skill looks valid.
+pub fn validate_skill_soft(skill_dir: &Path, expected_dir_name: &str) -> bool {
     let skill_md_path = skill_dir.join("SKILL.md");
 
     // 1. Check if SKILL.md exists
@@ -17,15 +18,15 @@ pub fn validate_skill_soft(skill_dir: &Path, expected_dir_name: &str) {
             "  {} SKILL.md not found. This might not be a valid Agent Skill.",
             "⚠️  Warning:".yellow().bold()
         );
-        return;
+        return false;
     }
 
     // 2. Read and parse frontmatter
     if let Ok(content) = fs::read_to_string(&skill_md_path) {
-        if content.starts_with("---") {
+        if let Some(rest) = content.strip_prefix("---") {
             // Find the end of the frontmatter block
-            if let Some(end_idx) = content[3..].find("---") {
-                let frontmatter_str = &content[3..end_idx + 3];
+            if let Some(end_idx) = rest.find("---") {
+                let frontmatter_str = &rest[..end_idx];
                 
                 match serde_yaml::from_str::<SkillFrontmatter>(frontmatter_str) {
                     Ok(frontmatter) => {
@@ -35,7 +36,7 @@ pub fn validate_skill_soft(skill_dir: &Path, expected_dir_name: &str) {
                                 "  {} 'name' field missing in SKILL.md frontmatter.",
                                 "⚠️  Warning:".yellow().bold()
                             );
-                            return;
+                            return false;
                         }
 
                         // 4. Directory name mismatch
@@ -47,13 +48,16 @@ pub fn validate_skill_soft(skill_dir: &Path, expected_dir_name: &str) {
                                 expected_dir_name,
                                 actual_name
                             );
+                            return false;
                         }
+                        true
                     }
                     Err(_) => {
                         println!(
                             "  {} Failed to parse YAML frontmatter in SKILL.md.",
                             "⚠️  Warning:".yellow().bold()
                         );
+                        false
                     }
                 }
             } else {
@@ -61,18 +65,21 @@ pub fn validate_skill_soft(skill_dir: &Path, expected_dir_name: &str) {
                     "  {} Invalid frontmatter format in SKILL.md (missing closing '---').",
                     "⚠️  Warning:".yellow().bold()
                 );
+                false
             }
         } else {
             println!(
                 "  {} SKILL.md is missing YAML frontmatter at the top of the file.",
                 "⚠️  Warning:".yellow().bold()
             );
+            false
         }
     } else {
         println!(
             "  {} Failed to read SKILL.md.",
             "⚠️  Warning:".yellow().bold()
         );
+        false
     }
 }
 
@@ -91,7 +98,7 @@ mod tests {
         writeln!(file, "---\nname: my-skill\ndescription: Test\n---\nBody").unwrap();
 
         // This should not panic and ideally print nothing (or we just ensure it executes cleanly)
-        validate_skill_soft(dir.path(), "my-skill");
+        assert!(validate_skill_soft(dir.path(), "my-skill"));
     }
 
     // Since validation just prints warnings, we can't easily assert on output without intercepting stdout,
@@ -120,6 +127,6 @@ mod tests {
         let mut file = File::create(skill_md).unwrap();
         writeln!(file, "---\nname: wrong-name\n---\nBody").unwrap();
         
-        validate_skill_soft(dir.path(), "my-skill");
+        assert!(!validate_skill_soft(dir.path(), "my-skill"));
     }
 }
@@ -0,0 +1,174 @@
+use std::cmp::Ordering;
+
+/// Kind of a ref advertised by a remote repository.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefKind {
+    Branch,
+    Tag,
+}
+
+/// A branch or tag on a remote, already peeled to the commit it points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteRef {
+    pub name: String,
+    pub kind: RefKind,
+    pub commit: String,
+    /// Committer date (`YYYY-MM-DD`), when the commit is available locally.
+    pub date: Option<String>,
+}
+
+/// A semantic version parsed from a tag name such as `v1.2.3` or `2.0.0-rc.1`.
+/// Missing minor/patch components are treated as zero (`v2` == `2.0.0`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<String>,
+}
+
+impl Version {
+    pub fn parse(tag: &str) -> Option<Self> {
+        let s = tag.strip_prefix('v').or_else(|| tag.strip_prefix('V')).unwrap_or(tag);
+        // Build metadata never affects precedence
+        let s = s.split('+').next().unwrap_or(s);
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (s, None),
+        };
+
+        let mut nums = core.split('.');
+        let major = nums.next()?.parse().ok()?;
+        let minor = match nums.next() { Some(n) => n.parse().ok()?, None => 0 };
+        let patch = match nums.next() { Some(n) => n.parse().ok()?, None => 0 };
+        if nums.next().is_some() {
+            return None;
+        }
+
+        let pre = match pre {
+            Some("") => return None,
+            Some(p) => p.split('.').map(|s| s.to_string()).collect(),
+            None => Vec::new(),
+        };
+
+        Some(Self { major, minor, patch, pre })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                // A release outranks any of its pre-releases
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => cmp_prerelease(&self.pre, &other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn cmp_prerelease(a: &[String], b: &[String]) -> Ordering {
+    for (x, y) in a.iter().zip(b.iter()) {
+        let ord = match (x.parse::<u64>(), y.parse::<u64>()) {
+            (Ok(x), Ok(y)) => x.cmp(&y),
+            (Ok(_), Err(_)) => Ordering::Less,
+            (Err(_), Ok(_)) => Ordering::Greater,
+            (Err(_), Err(_)) => x.cmp(y),
+        };
+        if ord != Ordering::Equal {
+            return ord;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// Picks the tag with the highest stable semantic version.
+/// Tags that are not valid versions, and pre-releases, are ignored.
+pub fn newest_release_tag(refs: &[RemoteRef]) -> Option<&RemoteRef> {
+    refs.iter()
+        .filter(|r| r.kind == RefKind::Tag)
+        .filter_map(|r| Version::parse(&r.name).map(|v| (v, r)))
+        .filter(|(v, _)| !v.is_prerelease())
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, r)| r)
+}
+
+/// Orders refs for display: tags first, newest semantic version first
+/// (non-version tags after, by name), then branches by name.
+pub fn sort_refs(refs: &mut [RemoteRef]) {
+    refs.sort_by(|a, b| match (a.kind, b.kind) {
+        (RefKind::Tag, RefKind::Branch) => Ordering::Less,
+        (RefKind::Branch, RefKind::Tag) => Ordering::Greater,
+        (RefKind::Branch, RefKind::Branch) => a.name.cmp(&b.name),
+        (RefKind::Tag, RefKind::Tag) => match (Version::parse(&a.name), Version::parse(&b.name)) {
+            (Some(va), Some(vb)) => vb.cmp(&va).then_with(|| a.name.cmp(&b.name)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => a.name.cmp(&b.name),
+        },
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str) -> RemoteRef {
+        RemoteRef { name: name.to_string(), kind: RefKind::Tag, commit: format!("{}-commit", name), date: None }
+    }
+
+    #[test]
+    fn test_parse_versions() {
+        assert_eq!(Version::parse("v1.2.3"), Some(Version { major: 1, minor: 2, patch: 3, pre: vec![] }));
+        assert_eq!(Version::parse("2"), Some(Version { major: 2, minor: 0, patch: 0, pre: vec![] }));
+        assert!(Version::parse("v1.0.0-rc.1").unwrap().is_prerelease());
+        assert!(Version::parse("release-2024").is_none());
+        assert!(Version::parse("1.2.3.4").is_none());
+        assert!(Version::parse("v1.0.0-").is_none());
+    }
+
+    #[test]
+    fn test_version_ordering() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert!(v("v1.10.0") > v("v1.9.9"));
+        assert!(v("1.0.0") > v("1.0.0-rc.2"));
+        assert!(v("1.0.0-rc.10") > v("1.0.0-rc.2"));
+        assert!(v("1.0.0-beta") > v("1.0.0-alpha.1"));
+        assert!(v("1.0.0-alpha.1") > v("1.0.0-alpha"));
+        assert_eq!(v("v2").cmp(&v("2.0.0")), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_newest_release_tag() {
+        let refs = vec![
+            tag("v1.2.0"),
+            tag("v1.10.0"),
+            tag("v2.0.0-rc.1"),
+            tag("nightly"),
+            RemoteRef { name: "v9.0.0".to_string(), kind: RefKind::Branch, commit: "b".to_string(), date: None },
+        ];
+        assert_eq!(newest_release_tag(&refs).unwrap().name, "v1.10.0");
+        assert!(newest_release_tag(&[tag("latest")]).is_none());
+    }
+
+    #[test]
+    fn test_sort_refs() {
+        let branch = |n: &str| RemoteRef { name: n.to_string(), kind: RefKind::Branch, commit: String::new(), date: None };
+        let mut refs = vec![branch("main"), tag("nightly"), tag("v1.2.0"), branch("dev"), tag("v1.10.0"), tag("v1.10.0-rc.1")];
+        sort_refs(&mut refs);
+        let names: Vec<&str> = refs.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["v1.10.0", "v1.10.0-rc.1", "v1.2.0", "nightly", "dev", "main"]);
+    }
+}
@@ -0,0 +1,9 @@
+use std::path::Path;
+use crate::core::domain::config::Settings;
+use crate::core::domain::error::AppError;
+
+pub trait ConfigRepository {
+    /// A missing file is an empty layer, not an error.
+    fn load(&self, path: &Path) -> Result<Settings, AppError>;
+    fn save(&self, path: &Path, settings: &Settings) -> Result<(), AppError>;
+}
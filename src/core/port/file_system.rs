@@ -1,8 +1,19 @@
 use std::path::Path;
 use crate::core::domain::error::AppError;
 
-pub trait FileSystem {
+pub trait FileSystem: Sync {
     fn exists(&self, path: &Path) -> bool;
     fn remove_dir_all(&self, path: &Path) -> Result<(), AppError>;
     fn move_dir(&self, src: &Path, dest: &Path) -> Result<(), AppError>;
+    /// Replaces `dest` with a copy of the directory `src`.
+    fn copy_dir(&self, src: &Path, dest: &Path) -> Result<(), AppError>;
+    /// Creates `link` as a symbolic link pointing at the directory `target`.
+    fn symlink_dir(&self, target: &Path, link: &Path) -> Result<(), AppError>;
+    /// Recreates the directory structure of `src` at `dest`, hard-linking every file.
+    fn hardlink_tree(&self, src: &Path, dest: &Path) -> Result<(), AppError>;
+    /// True when `path` itself is a symbolic link (without following it).
+    fn is_symlink(&self, path: &Path) -> bool;
+    fn read_to_string(&self, path: &Path) -> Result<String, AppError>;
+    /// Writes `contents` to the file at `path`, creating its parent directories.
+    fn write_file(&self, path: &Path, contents: &str) -> Result<(), AppError>;
 }
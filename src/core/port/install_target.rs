@@ -0,0 +1,24 @@
+use std::path::{Path, PathBuf};
+use crate::core::domain::dependency::Dependency;
+use crate::core::domain::error::AppError;
+
+/// A skill whose folder has been placed into a target directory, at `<target>/<key>`.
+pub struct PlacedSkill<'p> {
+    pub key: &'p str,
+    /// The skill's manifest entry, with any per-skill options for adapters
+    pub dependency: &'p Dependency,
+    pub folder: &'p Path,
+    pub target_dir: &'p Path,
+    pub project_dir: &'p Path,
+}
+
+/// Turns installed skills into what one kind of agent reads from its target directory.
+///
+/// Every target gets the skill's folder; an adapter writes anything else the agent needs
+/// (e.g. a rule file generated from `SKILL.md`) and names it so it is removed with the skill.
+pub trait InstallTarget: Sync {
+    fn install(&self, skill: &PlacedSkill) -> Result<(), AppError>;
+
+    /// Files the adapter writes for skill `key` into `target_dir`.
+    fn outputs(&self, target_dir: &Path, key: &str) -> Vec<PathBuf>;
+}
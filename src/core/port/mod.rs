@@ -2,3 +2,8 @@ pub mod manifest_repository;
 pub mod skill_fetcher;
 pub mod file_system;
 pub mod lockfile_repository;
+pub mod skill_store;
+pub mod repository_cache;
+pub mod config_repository;
+pub mod prompter;
+pub mod install_target;
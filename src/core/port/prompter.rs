@@ -0,0 +1,10 @@
+use crate::core::domain::error::AppError;
+
+/// Asks the user for answers during interactive commands.
+pub trait Prompter {
+    /// Asks for a line of text. An empty answer returns `default` (or an empty string).
+    fn input(&self, label: &str, default: Option<&str>) -> Result<String, AppError>;
+
+    /// Asks a yes/no question. An empty answer returns `default`.
+    fn confirm(&self, label: &str, default: bool) -> Result<bool, AppError>;
+}
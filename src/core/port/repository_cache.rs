@@ -0,0 +1,19 @@
+use std::path::Path;
+use crate::core::domain::cache::CachedRepository;
+use crate::core::domain::error::AppError;
+
+/// The local cache of git repositories that skills are fetched from.
+pub trait RepositoryCache {
+    fn root(&self) -> &Path;
+
+    fn list(&self) -> Result<Vec<CachedRepository>, AppError>;
+
+    /// Deletes a cached repository; it is cloned again the next time it is needed.
+    fn remove(&self, repository: &str) -> Result<(), AppError>;
+
+    /// Checks a cached repository's integrity; the error describes what is broken.
+    fn verify(&self, repository: &str) -> Result<(), AppError>;
+
+    /// Repacks a cached repository and drops leftover metadata to reclaim disk space.
+    fn compact(&self, repository: &str) -> Result<(), AppError>;
+}
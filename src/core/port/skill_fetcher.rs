@@ -1,12 +1,37 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use crate::core::domain::skill::SkillSource;
+use crate::core::domain::version::RemoteRef;
+use crate::core::domain::lockfile::LockEntry;
 use crate::core::domain::error::AppError;
 
-pub trait SkillFetcher {
-    /// Returns the resolved Git commit hash that was fetched/copied
-    fn fetch(&self, source: &SkillSource, dest: &Path, target_commit: Option<&str>) -> Result<String, AppError>;
+/// Implementations must be shareable across threads: installs and updates fetch skills concurrently.
+pub trait SkillFetcher: Sync {
+    /// Returns the resolved Git commit and folder tree hash that were fetched/copied
+    fn fetch(&self, source: &SkillSource, dest: &Path, target_commit: Option<&str>) -> Result<LockEntry, AppError> {
+        let mut results = self.fetch_batch(&[(source.clone(), dest.to_path_buf())], target_commit)?;
+        results.pop().unwrap_or_else(|| Err(AppError::System(format!("Nothing fetched for {}", source.as_str()))))
+    }
+
+    /// Fetches several skills that live in the same repository at the same ref (or `target_commit`)
+    /// with a single cache update and checkout.
+    /// The outer error fails the whole batch (e.g. clone failed); inner results are per skill, in input order.
+    fn fetch_batch(&self, items: &[(SkillSource, PathBuf)], target_commit: Option<&str>) -> Result<Vec<Result<LockEntry, AppError>>, AppError>;
+
+    /// Resolves the tree hash of each source's folder at `commit` without copying files,
+    /// updating the cache only if it does not have the commit yet.
+    /// `None` for a folder that does not exist at that commit.
+    fn resolve_trees(&self, sources: &[SkillSource], commit: &str) -> Result<Vec<Option<String>>, AppError>;
 
     /// Resolves the latest remote commit hash for the given source without copying files.
     /// Used by `update` to skip expensive I/O when hashes already match.
-    fn resolve_remote_hash(&self, source: &SkillSource) -> Result<String, AppError>;
+    fn resolve_remote_hash(&self, source: &SkillSource) -> Result<String, AppError> {
+        let mut hashes = self.resolve_remote_hashes(std::slice::from_ref(source))?;
+        hashes.pop().ok_or_else(|| AppError::System(format!("Could not resolve {}", source.as_str())))
+    }
+
+    /// Resolves several sources of the same repository with a single remote round trip.
+    fn resolve_remote_hashes(&self, sources: &[SkillSource]) -> Result<Vec<String>, AppError>;
+
+    /// Lists the branches and tags advertised by the source's repository, peeled to commits.
+    fn list_refs(&self, source: &SkillSource) -> Result<Vec<RemoteRef>, AppError>;
 }
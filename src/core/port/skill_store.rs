@@ -0,0 +1,23 @@
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use crate::core::domain::error::AppError;
+
+/// Global content-addressed store of skill folders shared by every project,
+/// keyed by the folder's git tree hash.
+pub trait SkillStore: Sync {
+    fn root(&self) -> &Path;
+
+    fn path(&self, integrity: &str) -> PathBuf {
+        self.root().join(integrity)
+    }
+
+    fn contains(&self, integrity: &str) -> bool;
+
+    /// A fresh directory on the store's filesystem, so staged folders are added with a rename.
+    fn staging_dir(&self) -> Result<TempDir, AppError>;
+
+    /// Adds the folder at `staged` as `integrity` with a single rename and makes it read-only, so
+    /// edits through one project's link cannot change what other projects share. When another
+    /// process stored the same content first, that copy is kept and `staged` is discarded.
+    fn add(&self, integrity: &str, staged: &Path) -> Result<(), AppError>;
+}
@@ -0,0 +1,125 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use colored::Colorize;
+use crate::core::domain::error::AppError;
+use crate::core::domain::skill::SkillSource;
+use crate::core::domain::lockfile::LockEntry;
+use crate::core::port::skill_fetcher::SkillFetcher;
+use crate::core::usecase::parallel::run_limited;
+
+/// Skills that can be served by a single fetch and checkout: same repository, same target.
+struct FetchGroup {
+    keys: Vec<String>,
+    items: Vec<(SkillSource, PathBuf)>,
+    target_commit: Option<String>,
+}
+
+/// Collects the skills to fetch and groups them by `(repository, commit or ref)`,
+/// so a monorepo hosting many skills is fetched and checked out once.
+#[derive(Default)]
+pub struct FetchPlan {
+    groups: BTreeMap<(String, String), FetchGroup>,
+}
+
+impl FetchPlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, key: &str, source: &SkillSource, dest: PathBuf, target_commit: Option<&str>) {
+        let target = target_commit.or(source.split_ref().1).unwrap_or("HEAD");
+        let group = self.groups
+            .entry((source.repository().to_string(), target.to_string()))
+            .or_insert_with(|| FetchGroup {
+                keys: Vec::new(),
+                items: Vec::new(),
+                target_commit: target_commit.map(|c| c.to_string()),
+            });
+        group.keys.push(key.to_string());
+        group.items.push((source.clone(), dest));
+    }
+
+    /// Fetches every group on at most `jobs` threads. Returns the resolved lock entry (or error) per skill key.
+    pub fn run<S: SkillFetcher>(self, fetcher: &S, jobs: usize) -> HashMap<String, Result<LockEntry, AppError>> {
+        let groups: Vec<((String, String), FetchGroup)> = self.groups.into_iter().collect();
+
+        // Announced from this thread, before the workers start, so the lines never interleave
+        for ((repository, target), group) in &groups {
+            println!("  {} {}@{} ({})...", "Fetching".yellow(), repository, target, group.keys.join(", "));
+        }
+        let results = run_limited(&groups, jobs, |(_, group)| {
+            fetcher.fetch_batch(&group.items, group.target_commit.as_deref())
+        });
+
+        let mut by_key = HashMap::new();
+        for ((_, group), result) in groups.into_iter().zip(results) {
+            match result {
+                Ok(per_skill) => by_key.extend(group.keys.into_iter().zip(per_skill)),
+                // A repository-level failure applies to every skill in the group
+                Err(e) => by_key.extend(group.keys.into_iter().map(|k| (k, Err(e.clone())))),
+            }
+        }
+        by_key
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+    use std::sync::Mutex;
+    use crate::core::domain::version::RemoteRef;
+
+    /// Records every batch it is asked to fetch.
+    struct RecordingFetcher {
+        batches: Mutex<Vec<(Vec<String>, Option<String>)>>,
+    }
+
+    impl SkillFetcher for RecordingFetcher {
+        fn fetch_batch(&self, items: &[(SkillSource, PathBuf)], target_commit: Option<&str>) -> Result<Vec<Result<LockEntry, AppError>>, AppError> {
+            let sources = items.iter().map(|(s, _)| s.as_str().to_string()).collect();
+            self.batches.lock().unwrap().push((sources, target_commit.map(|c| c.to_string())));
+            if items.iter().any(|(s, _)| s.repository() == "org/broken") {
+                return Err(AppError::Auth("github.com needs credentials".to_string()));
+            }
+            Ok(items.iter().map(|_| Ok(LockEntry::new(target_commit.unwrap_or("resolved").to_string(), "tree".to_string()))).collect())
+        }
+
+        fn resolve_trees(&self, _sources: &[SkillSource], _commit: &str) -> Result<Vec<Option<String>>, AppError> {
+            Ok(Vec::new())
+        }
+
+        fn resolve_remote_hashes(&self, _sources: &[SkillSource]) -> Result<Vec<String>, AppError> {
+            Ok(Vec::new())
+        }
+
+        fn list_refs(&self, _source: &SkillSource) -> Result<Vec<RemoteRef>, AppError> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn test_groups_by_repository_and_target() {
+        let fetcher = RecordingFetcher { batches: Mutex::new(Vec::new()) };
+        let dest = Path::new("/tmp/skills");
+
+        let mut plan = FetchPlan::new();
+        plan.add("a", &SkillSource::new("org/mono/skills/a#main"), dest.join("a"), None);
+        plan.add("b", &SkillSource::new("org/mono/skills/b#main"), dest.join("b"), None);
+        plan.add("c", &SkillSource::new("org/mono/skills/c#main"), dest.join("c"), Some("abc123"));
+        plan.add("d", &SkillSource::new("org/other"), dest.join("d"), None);
+        plan.add("e", &SkillSource::new("org/broken/e"), dest.join("e"), None);
+
+        let results = plan.run(&fetcher, 2);
+
+        let mut batches = fetcher.batches.into_inner().unwrap();
+        batches.sort();
+        assert_eq!(batches.len(), 4);
+        assert!(batches.contains(&(vec!["org/mono/skills/a#main".to_string(), "org/mono/skills/b#main".to_string()], None)));
+        assert!(batches.contains(&(vec!["org/mono/skills/c#main".to_string()], Some("abc123".to_string()))));
+
+        assert_eq!(results["a"].as_ref().unwrap().commit, "resolved");
+        assert_eq!(results["c"].as_ref().unwrap().commit, "abc123");
+        assert!(matches!(results["e"], Err(AppError::Auth(_))));
+    }
+}
@@ -0,0 +1,95 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use colored::Colorize;
+use crate::core::domain::cache::{select_evictions, CachedRepository};
+use crate::core::domain::error::AppError;
+use crate::core::port::repository_cache::RepositoryCache;
+
+pub struct CacheUseCase<'a, C: RepositoryCache> {
+    cache: &'a C,
+}
+
+impl<'a, C: RepositoryCache> CacheUseCase<'a, C> {
+    pub fn new(cache: &'a C) -> Self {
+        Self { cache }
+    }
+
+    /// Cached repositories, most recently used first.
+    pub fn list(&self) -> Result<Vec<CachedRepository>, AppError> {
+        let mut repos = self.cache.list()?;
+        repos.sort_by(|a, b| b.last_used.cmp(&a.last_used).then_with(|| a.repository.cmp(&b.repository)));
+        Ok(repos)
+    }
+
+    /// Removes one repository, or the whole cache when `repository` is `None`.
+    pub fn clean(&self, repository: Option<&str>) -> Result<(), AppError> {
+        let targets = match repository {
+            Some(repository) => vec![repository.to_string()],
+            None => self.cache.list()?.into_iter().map(|r| r.repository).collect(),
+        };
+
+        for repository in &targets {
+            self.cache.remove(repository)?;
+            println!("  {} Removed {}", "✔".green(), repository);
+        }
+        println!("{} Removed {} cached repositories", "✔".green().bold(), targets.len());
+        Ok(())
+    }
+
+    /// Runs an integrity check on one repository, or on every cached repository.
+    pub fn verify(&self, repository: Option<&str>) -> Result<(), AppError> {
+        let targets = match repository {
+            Some(repository) => vec![repository.to_string()],
+            None => self.cache.list()?.into_iter().map(|r| r.repository).collect(),
+        };
+
+        let mut broken = Vec::new();
+        for repository in &targets {
+            match self.cache.verify(repository) {
+                Ok(()) => println!("  {} {}", "✔".green(), repository),
+                Err(e) => {
+                    println!("  {} {}: {}", "✖".red(), repository, e);
+                    broken.push(repository.clone());
+                }
+            }
+        }
+
+        if !broken.is_empty() {
+            return Err(AppError::System(format!(
+                "{} of {} cached repositories are damaged: {} (remove them with `arsync cache clean <repo>`; they are cloned again when next needed)",
+                broken.len(), targets.len(), broken.join(", ")
+            )));
+        }
+
+        println!("{} Verified {} cached repositories", "✔".green().bold(), targets.len());
+        Ok(())
+    }
+
+    /// Evicts repositories unused for `max_age` seconds and least recently used ones beyond `max_size` bytes,
+    /// then compacts the ones that remain.
+    pub fn gc(&self, max_age: Option<u64>, max_size: Option<u64>) -> Result<(), AppError> {
+        let repos = self.cache.list()?;
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let evicted = select_evictions(&repos, now, max_age, max_size);
+
+        for repository in &evicted {
+            self.cache.remove(repository)?;
+            println!("  {} Evicted {}", "✔".green(), repository);
+        }
+
+        let mut failed = Vec::new();
+        for repo in repos.iter().filter(|r| !evicted.contains(&r.repository)) {
+            println!("  {} {}...", "Compacting".yellow(), repo.repository);
+            if let Err(e) = self.cache.compact(&repo.repository) {
+                println!("  {} {}: {}", "✖".red(), repo.repository, e);
+                failed.push(repo.repository.clone());
+            }
+        }
+
+        if !failed.is_empty() {
+            return Err(AppError::System(format!("Failed to compact {}", failed.join(", "))));
+        }
+
+        println!("{} Evicted {} and compacted {} cached repositories", "✔".green().bold(), evicted.len(), repos.len() - evicted.len());
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+use colored::Colorize;
+use crate::core::domain::catalog::{render_catalog, skill_description, splice_catalog, CatalogEntry, CATALOG_FILES};
+use crate::core::domain::error::AppError;
+use crate::core::domain::manifest::Manifest;
+use crate::core::port::file_system::FileSystem;
+use crate::core::port::manifest_repository::ManifestRepository;
+
+/// Keeps a section listing the installed skills in instruction files such as `AGENTS.md`,
+/// for agents that read those files but do not discover skill folders.
+pub struct CatalogUseCase<'a, M: ManifestRepository, F: FileSystem> {
+    manifest_repo: &'a M,
+    file_system: &'a F,
+}
+
+impl<'a, M: ManifestRepository, F: FileSystem> CatalogUseCase<'a, M, F> {
+    pub fn new(manifest_repo: &'a M, file_system: &'a F) -> Self {
+        Self { manifest_repo, file_system }
+    }
+
+    /// Writes the catalog into `files`. Without any, into the manifest's `catalog` files, else into
+    /// whichever of `AGENTS.md`, `CLAUDE.md` and `GEMINI.md` exist, else into a new `AGENTS.md`.
+    pub fn execute(&self, dir: &Path, files: &[PathBuf]) -> Result<(), AppError> {
+        let manifest = self.manifest_repo.load(&dir.join("skills.arsync"))?;
+
+        let mut files = files.to_vec();
+        if files.is_empty() {
+            files = manifest.catalog.clone();
+        }
+        if files.is_empty() {
+            files = CATALOG_FILES.iter().map(PathBuf::from).filter(|f| self.file_system.exists(&dir.join(f))).collect();
+        }
+        if files.is_empty() {
+            files.push(PathBuf::from(CATALOG_FILES[0]));
+        }
+        self.write(&manifest, dir, &files)
+    }
+
+    /// Rewrites the manifest's `catalog` files, if it names any.
+    pub fn refresh(&self, dir: &Path) -> Result<(), AppError> {
+        let manifest = self.manifest_repo.load(&dir.join("skills.arsync"))?;
+        if manifest.catalog.is_empty() {
+            return Ok(());
+        }
+        self.write(&manifest, dir, &manifest.catalog)
+    }
+
+    fn write(&self, manifest: &Manifest, dir: &Path, files: &[PathBuf]) -> Result<(), AppError> {
+        let section = render_catalog(&self.entries(manifest, dir));
+        for file in files {
+            let path = dir.join(file);
+            let document = if self.file_system.exists(&path) { self.file_system.read_to_string(&path)? } else { String::new() };
+            let updated = splice_catalog(&document, &section);
+            if updated == document {
+                println!("{} {} is up to date", "✔".green().bold(), file.display());
+            } else {
+                self.file_system.write_file(&path, &updated)?;
+                println!("{} Updated the skills catalog in {}", "✔".green().bold(), file.display());
+            }
+        }
+        Ok(())
+    }
+
+    fn entries(&self, manifest: &Manifest, dir: &Path) -> Vec<CatalogEntry> {
+        let mut keys: Vec<&String> = manifest.dependencies.keys().collect();
+        keys.sort();
+
+        let mut entries = Vec::new();
+        for key in keys {
+            let skill_md = manifest.install_paths(dir, key).into_iter()
+                .map(|path| path.join("SKILL.md"))
+                .find(|path| self.file_system.exists(path));
+            let Some(skill_md) = skill_md else {
+                println!("  {} {} is not installed, leaving it out of the catalog (run `arsync install`)", "⚠️".yellow(), key);
+                continue;
+            };
+            let description = self.file_system.read_to_string(&skill_md).ok()
+                .and_then(|content| skill_description(&content))
+                .unwrap_or_default();
+            let path = skill_md.strip_prefix(dir).unwrap_or(&skill_md).to_string_lossy().replace('\\', "/");
+            entries.push(CatalogEntry { name: key.clone(), description, path });
+        }
+        entries
+    }
+}
@@ -0,0 +1,157 @@
+use std::path::Path;
+use crate::core::domain::config::{Settings, KEYS};
+use crate::core::domain::error::AppError;
+use crate::core::port::config_repository::ConfigRepository;
+
+/// Which layer a setting's effective value comes from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    User,
+    Project,
+    Env,
+}
+
+impl ConfigSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConfigSource::Default => "default",
+            ConfigSource::User => "user",
+            ConfigSource::Project => "project",
+            ConfigSource::Env => "env",
+        }
+    }
+}
+
+/// One row of `config list`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigEntry {
+    pub key: String,
+    /// `None` when no layer sets the key
+    pub value: Option<String>,
+    pub source: ConfigSource,
+}
+
+/// Layered settings, lowest precedence first: user config file, project config file, environment.
+/// Command-line flags override the result at the call site.
+pub struct ConfigUseCase<'a, C: ConfigRepository> {
+    config_repo: &'a C,
+    user_path: &'a Path,
+    project_path: &'a Path,
+    env: Settings,
+}
+
+impl<'a, C: ConfigRepository> ConfigUseCase<'a, C> {
+    pub fn new(config_repo: &'a C, user_path: &'a Path, project_path: &'a Path, env: Settings) -> Self {
+        Self { config_repo, user_path, project_path, env }
+    }
+
+    fn layers(&self) -> Result<[(ConfigSource, Settings); 3], AppError> {
+        Ok([
+            (ConfigSource::Env, self.env.clone()),
+            (ConfigSource::Project, self.project_settings()?),
+            (ConfigSource::User, self.config_repo.load(self.user_path)?),
+        ])
+    }
+
+    /// The project config file without its tokens: it is usually committed, so tokens come only from
+    /// the user config or the environment.
+    fn project_settings(&self) -> Result<Settings, AppError> {
+        let mut settings = self.config_repo.load(self.project_path)?;
+        settings.tokens.clear();
+        Ok(settings)
+    }
+
+    /// The file `set`/`unset` write to; tokens are refused for the project config.
+    fn target_path(&self, key: &str, project: bool) -> Result<&'a Path, AppError> {
+        if !project {
+            return Ok(self.user_path);
+        }
+        if key.starts_with("token.") {
+            return Err(AppError::Config(format!("'{}' can't be set in the project config, which is usually committed; set it in the user config or with ARSYNC_TOKEN_<HOST>", key)));
+        }
+        Ok(self.project_path)
+    }
+
+    pub fn effective(&self) -> Result<Settings, AppError> {
+        let [(_, env), (_, project), (_, user)] = self.layers()?;
+        Ok(user.overridden_by(project).overridden_by(env))
+    }
+
+    /// The effective value of `key` and the layer it comes from (`None` means the built-in default applies).
+    pub fn get(&self, key: &str) -> Result<(Option<String>, ConfigSource), AppError> {
+        for (source, layer) in self.layers()? {
+            if let Some(value) = layer.get(key)? {
+                return Ok((Some(value), source));
+            }
+        }
+        Ok((None, ConfigSource::Default))
+    }
+
+    /// Every setting, followed by the `<table>.<name>` entries that are set. Token values are masked.
+    pub fn list(&self) -> Result<Vec<ConfigEntry>, AppError> {
+        let keys = KEYS.iter().map(|k| k.to_string()).chain(self.effective()?.table_keys());
+        keys.map(|key| {
+            let (value, source) = self.get(&key)?;
+            let value = if key.starts_with("token.") { value.map(|_| "********".to_string()) } else { value };
+            Ok(ConfigEntry { key, value, source })
+        }).collect()
+    }
+
+    /// Writes `key` to the user config file, or to the project's when `project` is set.
+    pub fn set(&self, key: &str, value: &str, project: bool) -> Result<(), AppError> {
+        let path = self.target_path(key, project)?;
+        let mut settings = self.config_repo.load(path)?;
+        settings.set(key, value)?;
+        self.config_repo.save(path, &settings)
+    }
+
+    pub fn unset(&self, key: &str, project: bool) -> Result<(), AppError> {
+        let path = self.target_path(key, project)?;
+        let mut settings = self.config_repo.load(path)?;
+        settings.unset(key)?;
+        self.config_repo.save(path, &settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    #[derive(Default)]
+    struct MemoryConfigRepository {
+        files: RefCell<HashMap<PathBuf, Settings>>,
+    }
+
+    impl ConfigRepository for MemoryConfigRepository {
+        fn load(&self, path: &Path) -> Result<Settings, AppError> {
+            Ok(self.files.borrow().get(path).cloned().unwrap_or_default())
+        }
+
+        fn save(&self, path: &Path, settings: &Settings) -> Result<(), AppError> {
+            self.files.borrow_mut().insert(path.to_path_buf(), settings.clone());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_tokens_stay_out_of_the_project_config() {
+        let repo = MemoryConfigRepository::default();
+        let (user, project) = (Path::new("user.toml"), Path::new("project.toml"));
+        let mut committed = Settings::default();
+        committed.set("token.github.com", "leaked").unwrap();
+        repo.save(project, &committed).unwrap();
+        let config = ConfigUseCase::new(&repo, user, project, Settings::default());
+
+        assert!(matches!(config.set("token.github.com", "secret", true), Err(AppError::Config(_))));
+        assert!(matches!(config.unset("token.github.com", true), Err(AppError::Config(_))));
+        assert_eq!(config.get("token.github.com").unwrap(), (None, ConfigSource::Default));
+
+        config.set("token.github.com", "secret", false).unwrap();
+        assert_eq!(config.get("token.github.com").unwrap(), (Some("secret".to_string()), ConfigSource::User));
+        assert_eq!(config.effective().unwrap().tokens["github.com"], "secret");
+    }
+}
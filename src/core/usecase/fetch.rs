@@ -0,0 +1,77 @@
+use std::path::Path;
+use colored::Colorize;
+use crate::core::domain::error::AppError;
+use crate::core::port::manifest_repository::ManifestRepository;
+use crate::core::port::skill_fetcher::SkillFetcher;
+use crate::core::port::lockfile_repository::LockfileRepository;
+use crate::core::usecase::batch::FetchPlan;
+
+/// Fills the fetcher's cache with everything the given projects need, so a later
+/// `install --offline` succeeds. Nothing is written into the projects themselves.
+pub struct FetchUseCase<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> {
+    manifest_repo: &'a M,
+    skill_fetcher: &'a S,
+    lockfile_repo: &'a L,
+    jobs: usize,
+}
+
+impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> FetchUseCase<'a, M, S, L> {
+    pub fn new(manifest_repo: &'a M, skill_fetcher: &'a S, lockfile_repo: &'a L) -> Self {
+        Self { manifest_repo, skill_fetcher, lockfile_repo, jobs: 1 }
+    }
+
+    /// Sets how many repositories may be fetched concurrently.
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// Fetches the locked commit (or the source's ref when unlocked) of every dependency
+    /// in the manifests of `dirs`.
+    pub fn execute(&self, dirs: &[&Path]) -> Result<(), AppError> {
+        let mut total = 0;
+        let mut failed = Vec::new();
+
+        for dir in dirs {
+            let manifest = self.manifest_repo.load(&dir.join("skills.arsync"))?;
+            let lockfile = self.lockfile_repo.load(&dir.join("skills-lock.arsync")).ok();
+
+            println!("{} Fetching dependencies of {}...", "=>".cyan().bold(), dir.join("skills.arsync").display());
+
+            // Skills are extracted into a throwaway directory: fetching them is what
+            // pulls the commit and its file contents into the cache
+            let scratch = tempfile::tempdir().map_err(|e| AppError::System(format!("Failed to create temp dir: {}", e)))?;
+
+            let mut keys: Vec<&String> = manifest.dependencies.keys().collect();
+            keys.sort();
+
+            let mut plan = FetchPlan::new();
+            for key in &keys {
+                let locked = lockfile.as_ref().and_then(|l| l.get_commit(key)).map(|s| s.as_str());
+                plan.add(key, &manifest.dependencies[key.as_str()].source, scratch.path().join(key), locked);
+            }
+            let mut results = plan.run(self.skill_fetcher, self.jobs);
+
+            for key in &keys {
+                total += 1;
+                match results.remove(key.as_str()).unwrap_or_else(|| Err(AppError::System(format!("Skill '{}' was not fetched", key)))) {
+                    Ok(entry) => println!("  {} {} ({})", "✔".green(), key, &entry.commit[..8.min(entry.commit.len())]),
+                    Err(e) => {
+                        println!("  {} {}: {}", "✖".red(), key, e);
+                        failed.push(key.to_string());
+                    }
+                }
+            }
+        }
+
+        if !failed.is_empty() {
+            return Err(AppError::System(format!(
+                "{} of {} skills could not be fetched: {}",
+                failed.len(), total, failed.join(", ")
+            )));
+        }
+
+        println!("{} Cached {} skills", "✔".green().bold(), total);
+        Ok(())
+    }
+}
@@ -1,33 +1,66 @@
-use std::path::Path;
-use crate::core::domain::manifest::Manifest;
+use std::path::{Path, PathBuf};
+use colored::Colorize;
+use crate::core::domain::agent::detect_agents;
+use crate::core::domain::config::DEFAULT_INSTALL_DIR;
+use crate::core::domain::lockfile::Lockfile;
+use crate::core::domain::manifest::{Manifest, Target};
 use crate::core::domain::skill::SkillName;
 use crate::core::domain::error::AppError;
 use crate::core::port::manifest_repository::ManifestRepository;
 use crate::core::port::lockfile_repository::LockfileRepository;
 use crate::core::port::file_system::FileSystem;
+use crate::core::port::prompter::Prompter;
 
-pub struct InitUseCase<'a, M: ManifestRepository, L: LockfileRepository, F: FileSystem> {
+/// Answers given up front (e.g. as flags); anything left out is asked for.
+#[derive(Debug, Clone, Default)]
+pub struct InitAnswers {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub install_dir: Option<String>,
+    /// Accept the defaults instead of asking
+    pub yes: bool,
+}
+
+pub struct InitUseCase<'a, M: ManifestRepository, L: LockfileRepository, F: FileSystem, P: Prompter> {
     manifest_repo: &'a M,
     lockfile_repo: &'a L,
     file_system: &'a F,
+    prompter: &'a P,
+    default_install_dir: String,
+    answers: InitAnswers,
 }
 
-impl<'a, M: ManifestRepository, L: LockfileRepository, F: FileSystem> InitUseCase<'a, M, L, F> {
-    pub fn new(manifest_repo: &'a M, lockfile_repo: &'a L, file_system: &'a F) -> Self {
-        Self { manifest_repo, lockfile_repo, file_system }
+impl<'a, M: ManifestRepository, L: LockfileRepository, F: FileSystem, P: Prompter> InitUseCase<'a, M, L, F, P> {
+    pub fn new(manifest_repo: &'a M, lockfile_repo: &'a L, file_system: &'a F, prompter: &'a P) -> Self {
+        Self {
+            manifest_repo,
+            lockfile_repo,
+            file_system,
+            prompter,
+            default_install_dir: DEFAULT_INSTALL_DIR.to_string(),
+            answers: InitAnswers::default(),
+        }
+    }
+
+    /// Sets the install directory suggested at the prompt.
+    pub fn with_default_install_dir(mut self, install_dir: String) -> Self {
+        self.default_install_dir = install_dir;
+        self
+    }
+
+    pub fn with_answers(mut self, answers: InitAnswers) -> Self {
+        self.answers = answers;
+        self
     }
 
     pub fn execute(&self, dir: &Path) -> Result<(), AppError> {
         let manifest_path = dir.join("skills.arsync");
         let lockfile_path = dir.join("skills-lock.arsync");
-        
+
         if self.file_system.exists(&manifest_path) || self.file_system.exists(&lockfile_path) {
             return Err(AppError::System("Manifest or Lockfile already exists in this directory".to_string()));
         }
 
-        use std::io::{self, Write};
-        use colored::Colorize;
-
         let default_name = dir.file_name()
             .and_then(|n| n.to_str())
             .unwrap_or("my-skill");
@@ -35,56 +68,182 @@ impl<'a, M: ManifestRepository, L: LockfileRepository, F: FileSystem> InitUseCas
         // Simple sanitization for the default suggestion
         let sanitized_default = default_name.to_lowercase().replace('_', "-");
 
-        // Prompt for Name
-        print!("name: ({}) ", sanitized_default.cyan());
-        io::stdout().flush().unwrap();
-        
-        let mut input_name = String::new();
-        io::stdin().read_line(&mut input_name).unwrap();
-        let input_name = input_name.trim();
-        
-        // Use user input if provided and valid, otherwise fallback to sanitized default
-        let final_name_str = if input_name.is_empty() {
-            sanitized_default.to_string()
-        } else {
-            input_name.to_string()
-        };
-
+        let final_name_str = self.answer(&self.answers.name, "name", Some(&sanitized_default))?;
         let skill_name = SkillName::new(&final_name_str)
             .map_err(|e| AppError::System(format!("Invalid skill name: {}", e)))?;
 
-        // Prompt for Description
-        print!("description: ");
-        io::stdout().flush().unwrap();
-        
-        let mut input_desc = String::new();
-        io::stdin().read_line(&mut input_desc).unwrap();
-        let final_desc = input_desc.trim().to_string();
-            
-        // Prompt for Install Directory
-        let default_install_dir = ".gemini/antigravity/skills";
-        print!("install directory: ({}) ", default_install_dir.cyan());
-        io::stdout().flush().unwrap();
-        
-        let mut input_dir = String::new();
-        io::stdin().read_line(&mut input_dir).unwrap();
-        let input_dir = input_dir.trim();
-        
-        let final_install_dir = if input_dir.is_empty() {
-            default_install_dir.to_string()
-        } else {
-            input_dir.to_string()
-        };
-        let final_install_path = std::path::PathBuf::from(final_install_dir);
-            
+        let final_desc = self.answer(&self.answers.description, "description", None)?;
+
         let mut manifest = Manifest::new(skill_name.clone(), final_desc.clone());
-        manifest.install_dir = Some(final_install_path.clone());
-        let lockfile = crate::core::domain::lockfile::Lockfile::new(skill_name, final_desc, Some(final_install_path));
-        
+
+        // Propose the skill directories of the agents already set up in the project,
+        // unless an install directory was given explicitly
+        let detected = detect_agents(|marker| self.file_system.exists(&dir.join(marker)));
+        if self.answers.install_dir.is_none() && !detected.is_empty() {
+            println!("detected agents:");
+            for agent in &detected {
+                println!("  {:<8} {}", agent.name, agent.dir);
+            }
+            if self.answers.yes || self.prompter.confirm("install skills into these targets?", true)? {
+                manifest.targets = detected.iter()
+                    .map(|agent| (agent.name.to_string(), Target::new(PathBuf::from(agent.dir), agent.kind)))
+                    .collect();
+            }
+        }
+
+        let mut final_install_path = None;
+        if manifest.targets.is_empty() {
+            let final_install_dir = self.answer(&self.answers.install_dir, "install directory", Some(&self.default_install_dir))?;
+            final_install_path = Some(PathBuf::from(final_install_dir));
+        }
+
+        manifest.install_dir = final_install_path.clone();
+        let lockfile = Lockfile::new(skill_name, final_desc, final_install_path);
+
         self.manifest_repo.save(&manifest_path, &manifest)?;
         self.lockfile_repo.save(&lockfile_path, &lockfile)?;
-        
+
         println!("\n{} Created skills.arsync and skills-lock.arsync", "✔".green().bold());
         Ok(())
     }
+
+    /// The given answer, the default under `--yes`, or the user's answer to the prompt.
+    fn answer(&self, given: &Option<String>, label: &str, default: Option<&str>) -> Result<String, AppError> {
+        match given {
+            Some(value) => Ok(value.clone()),
+            None if self.answers.yes => Ok(default.unwrap_or_default().to_string()),
+            None => self.prompter.input(label, default),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::{HashMap, HashSet, VecDeque};
+
+    /// A project tree that only knows which paths exist; init never touches files through it.
+    struct MarkerFs {
+        paths: HashSet<PathBuf>,
+    }
+
+    impl MarkerFs {
+        fn with(paths: &[&str]) -> Self {
+            Self { paths: paths.iter().map(PathBuf::from).collect() }
+        }
+
+        fn untouched() -> AppError {
+            AppError::System("not used by init".to_string())
+        }
+    }
+
+    impl FileSystem for MarkerFs {
+        fn exists(&self, path: &Path) -> bool { self.paths.contains(path) }
+        fn remove_dir_all(&self, _: &Path) -> Result<(), AppError> { Err(Self::untouched()) }
+        fn move_dir(&self, _: &Path, _: &Path) -> Result<(), AppError> { Err(Self::untouched()) }
+        fn copy_dir(&self, _: &Path, _: &Path) -> Result<(), AppError> { Err(Self::untouched()) }
+        fn symlink_dir(&self, _: &Path, _: &Path) -> Result<(), AppError> { Err(Self::untouched()) }
+        fn hardlink_tree(&self, _: &Path, _: &Path) -> Result<(), AppError> { Err(Self::untouched()) }
+        fn is_symlink(&self, _: &Path) -> bool { false }
+        fn read_to_string(&self, _: &Path) -> Result<String, AppError> { Err(Self::untouched()) }
+        fn write_file(&self, _: &Path, _: &str) -> Result<(), AppError> { Err(Self::untouched()) }
+    }
+
+    #[derive(Default)]
+    struct MemoryManifests(RefCell<HashMap<PathBuf, Manifest>>);
+
+    impl ManifestRepository for MemoryManifests {
+        fn load(&self, path: &Path) -> Result<Manifest, AppError> {
+            self.0.borrow().get(path).cloned().ok_or_else(|| AppError::Manifest(format!("{} not saved", path.display())))
+        }
+
+        fn save(&self, path: &Path, manifest: &Manifest) -> Result<(), AppError> {
+            self.0.borrow_mut().insert(path.to_path_buf(), manifest.clone());
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryLockfiles(RefCell<HashMap<PathBuf, Lockfile>>);
+
+    impl LockfileRepository for MemoryLockfiles {
+        fn load(&self, path: &Path) -> Result<Lockfile, AppError> {
+            self.0.borrow().get(path).cloned().ok_or_else(|| AppError::Manifest(format!("{} not saved", path.display())))
+        }
+
+        fn save(&self, path: &Path, lockfile: &Lockfile) -> Result<(), AppError> {
+            self.0.borrow_mut().insert(path.to_path_buf(), lockfile.clone());
+            Ok(())
+        }
+    }
+
+    /// Answers prompts from a script; running out of answers fails like a closed stdin.
+    struct ScriptedPrompter {
+        answers: RefCell<VecDeque<&'static str>>,
+    }
+
+    impl ScriptedPrompter {
+        fn new(answers: &[&'static str]) -> Self {
+            Self { answers: RefCell::new(answers.iter().copied().collect()) }
+        }
+
+        fn next(&self) -> Result<String, AppError> {
+            self.answers.borrow_mut().pop_front().map(str::to_string).ok_or_else(|| AppError::System("No input".to_string()))
+        }
+    }
+
+    impl Prompter for ScriptedPrompter {
+        fn input(&self, _label: &str, default: Option<&str>) -> Result<String, AppError> {
+            let answer = self.next()?;
+            Ok(if answer.is_empty() { default.unwrap_or_default().to_string() } else { answer })
+        }
+
+        fn confirm(&self, _label: &str, default: bool) -> Result<bool, AppError> {
+            let answer = self.next()?;
+            Ok(if answer.is_empty() { default } else { answer == "y" })
+        }
+    }
+
+    fn init(dir: &Path, fs: &MarkerFs, prompter: &ScriptedPrompter, answers: InitAnswers) -> Result<Manifest, AppError> {
+        let (manifest_repo, lockfile_repo) = (MemoryManifests::default(), MemoryLockfiles::default());
+        InitUseCase::new(&manifest_repo, &lockfile_repo, fs, prompter).with_answers(answers).execute(dir)?;
+        lockfile_repo.load(&dir.join("skills-lock.arsync"))?;
+        manifest_repo.load(&dir.join("skills.arsync"))
+    }
+
+    #[test]
+    fn test_prompts_fall_back_to_defaults() {
+        let dir = Path::new("/work/my_project");
+        let manifest = init(dir, &MarkerFs::with(&[]), &ScriptedPrompter::new(&["", "Shared skills", ""]), InitAnswers::default()).unwrap();
+        assert_eq!(manifest.name.as_str(), "my-project");
+        assert_eq!(manifest.description, "Shared skills");
+        assert_eq!(manifest.install_dir, Some(PathBuf::from(DEFAULT_INSTALL_DIR)));
+    }
+
+    #[test]
+    fn test_flags_and_yes_never_prompt() {
+        let dir = Path::new("/work/team");
+        let answers = InitAnswers { name: Some("team".to_string()), yes: true, ..Default::default() };
+
+        let manifest = init(dir, &MarkerFs::with(&["/work/team/.claude"]), &ScriptedPrompter::new(&[]), answers).unwrap();
+        assert_eq!(manifest.name.as_str(), "team");
+        assert_eq!(manifest.install_dir, None);
+        assert_eq!(manifest.targets["claude"].path, PathBuf::from(".claude/skills"));
+    }
+
+    #[test]
+    fn test_closed_input_is_an_error() {
+        let (manifest_repo, lockfile_repo, fs) = (MemoryManifests::default(), MemoryLockfiles::default(), MarkerFs::with(&[]));
+        let prompter = ScriptedPrompter::new(&["name"]);
+        assert!(InitUseCase::new(&manifest_repo, &lockfile_repo, &fs, &prompter).execute(Path::new("/work/p")).is_err());
+        assert!(manifest_repo.0.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_existing_manifest_is_kept() {
+        let fs = MarkerFs::with(&["/work/p/skills.arsync"]);
+        let answers = InitAnswers { yes: true, ..Default::default() };
+        assert!(init(Path::new("/work/p"), &fs, &ScriptedPrompter::new(&[]), answers).is_err());
+    }
 }
@@ -1,21 +1,53 @@
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use colored::Colorize;
 use crate::core::domain::skill::{SkillName, SkillSource};
+use crate::core::domain::dependency::Dependency;
+use crate::core::domain::manifest::Manifest;
 use crate::core::domain::error::AppError;
 use crate::core::port::manifest_repository::ManifestRepository;
 use crate::core::port::skill_fetcher::SkillFetcher;
 use crate::core::port::lockfile_repository::LockfileRepository;
+use crate::core::port::file_system::FileSystem;
+use crate::core::port::skill_store::SkillStore;
 use crate::core::domain::validation::validate_skill_soft;
+use crate::core::usecase::batch::FetchPlan;
+use crate::core::usecase::placement::Placement;
+use crate::core::usecase::targets::TargetAdapters;
 
-pub struct InstallUseCase<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> {
+pub struct InstallUseCase<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository, F: FileSystem, T: SkillStore> {
     manifest_repo: &'a M,
     skill_fetcher: &'a S,
     lockfile_repo: &'a L,
+    file_system: &'a F,
+    store: &'a T,
+    adapters: &'a TargetAdapters<'a>,
+    jobs: usize,
+    strict: bool,
+    targets: Vec<String>,
 }
 
-impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> InstallUseCase<'a, M, S, L> {
-    pub fn new(manifest_repo: &'a M, skill_fetcher: &'a S, lockfile_repo: &'a L) -> Self {
-        Self { manifest_repo, skill_fetcher, lockfile_repo }
+impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository, F: FileSystem, T: SkillStore> InstallUseCase<'a, M, S, L, F, T> {
+    pub fn new(manifest_repo: &'a M, skill_fetcher: &'a S, lockfile_repo: &'a L, file_system: &'a F, store: &'a T, adapters: &'a TargetAdapters<'a>) -> Self {
+        Self { manifest_repo, skill_fetcher, lockfile_repo, file_system, store, adapters, jobs: 1, strict: false, targets: Vec::new() }
+    }
+
+    /// Sets how many skills may be fetched concurrently when installing from the manifest.
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// Treats skill validation warnings as errors.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Limits a newly installed skill to these manifest targets (all targets when empty).
+    pub fn with_targets(mut self, targets: Vec<String>) -> Self {
+        self.targets = targets;
+        self
     }
 
     pub fn execute(&self, dir: &Path, source_opt: Option<String>) -> Result<(), AppError> {
@@ -31,7 +63,9 @@ impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> InstallU
             )
         });
 
-        let install_base = manifest.install_dir.clone().unwrap_or_else(|| dir.to_path_buf());
+        manifest.check_targets()?;
+        self.adapters.check(&manifest)?;
+        let placement = Placement::new(manifest.install_mode, self.file_system, self.store)?;
 
         if let Some(source_str) = source_opt {
             let source = SkillSource::new(&source_str);
@@ -41,51 +75,131 @@ impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> InstallU
             let skill_name = SkillName::new(&raw_name)?;
             let skill_key = skill_name.as_str().to_string();
 
-            if let Some(existing_source) = manifest.dependencies.get(&skill_key) {
-                if existing_source.as_str() != source_str {
+            if let Some(existing) = manifest.dependencies.get(&skill_key) {
+                if existing.source.as_str() != source_str {
                     return Err(AppError::System(format!(
                         "A different skill named '{}' is already installed from ({}).\nPlease uninstall it first before installing from {}.",
-                        skill_key, existing_source.as_str(), source_str
+                        skill_key, existing.source.as_str(), source_str
                     )));
                 }
             }
             
+            // Reinstalling an existing key keeps its per-dependency options (e.g. update policy)
+            let mut dependency = manifest.dependencies.get(&skill_key).cloned().unwrap_or_else(|| Dependency::new(source.clone()));
+            if !self.targets.is_empty() {
+                dependency.targets = Some(self.targets.clone());
+            }
+            manifest.add_dependency(skill_key.clone(), dependency);
+            manifest.check_targets()?;
+
             println!("{} {}...", "=> Installing".cyan().bold(), source.as_str());
 
-            let dest_path = install_base.join(&skill_key);
-            let commit_hash = self.skill_fetcher.fetch(&source, &dest_path, None)?;
+            // Fetched aside first, so a skill refused in strict mode leaves an existing install untouched
+            let dest_paths = manifest.install_paths(dir, &skill_key);
+            let staging = tempfile::tempdir().map_err(|e| AppError::System(format!("Failed to create temp dir: {}", e)))?;
+            let staged_path = placement.fetch_path(&skill_key, &staging.path().join(&skill_key));
+            let entry = self.skill_fetcher.fetch(&source, &staged_path, None)?;
 
-            // Run soft validation warnings
-            validate_skill_soft(&dest_path, &skill_key);
+            // Run soft validation warnings; in strict mode an invalid skill is not installed
+            if !validate_skill_soft(&staged_path, &skill_key) && self.strict {
+                return Err(AppError::Skill(format!("{} has validation warnings (strict mode), not installed", skill_key)));
+            }
+            placement.finish(&entry, &staged_path, &dest_paths)?;
+            self.adapters.install(&manifest, dir, &skill_key)?;
+            self.remove_excluded(&manifest, dir, &skill_key)?;
 
-            manifest.add_dependency(skill_key.clone(), source);
             self.manifest_repo.save(&manifest_path, &manifest)?;
 
-            lockfile.set_commit(skill_key.clone(), commit_hash);
+            lockfile.set_entry(skill_key.clone(), entry);
             let _ = self.lockfile_repo.save(&lockfile_path, &lockfile);
 
-            println!("{} Successfully installed {} to '{}'", "✔".green().bold(), source_str, dest_path.display());
+            let shown: Vec<String> = dest_paths.iter().map(|p| format!("'{}'", p.display())).collect();
+            println!("{} Successfully installed {} to {}", "✔".green().bold(), source_str, shown.join(", "));
         } else {
+            if !self.targets.is_empty() {
+                return Err(AppError::System("Targets can only be chosen when installing a single skill".to_string()));
+            }
             println!("{} Installing all dependencies from manifest...", "=>".cyan().bold());
+            let mut keys: Vec<&String> = manifest.dependencies.keys().collect();
+            keys.sort();
+
+            // If a hash is locked, we want to check that out specifically to guarantee identical environments across machines.
+            // Skills sharing a repository and commit are fetched together.
+            // When linking from the store, locked content that is already stored needs no fetch at all.
+            let mut plan = FetchPlan::new();
+            let mut linked = HashMap::new();
+            let dest_paths: HashMap<&String, Vec<PathBuf>> = keys.iter().map(|key| (*key, manifest.install_paths(dir, key))).collect();
+            for key in &keys {
+                let entry = lockfile.get_entry(key);
+                match placement.reuse(entry, &dest_paths[key]) {
+                    Ok(false) => {
+                        let source = &manifest.dependencies[key.as_str()].source;
+                        let fetch_path = placement.fetch_path(key, &dest_paths[key][0]);
+                        plan.add(key, source, fetch_path, lockfile.get_commit(key).map(|s| s.as_str()));
+                    }
+                    Ok(true) => {
+                        let entry = entry.cloned().ok_or_else(|| AppError::System(format!("Skill '{}' has no lock entry", key)));
+                        linked.insert(key.to_string(), entry);
+                    }
+                    Err(e) => { linked.insert(key.to_string(), Err(e)); }
+                }
+            }
+            let mut results = plan.run(self.skill_fetcher, self.jobs);
+
+            // Report sequentially so validation warnings stay next to the skill they belong to
             let mut count = 0;
-            for (key, source) in &manifest.dependencies {
-                println!("  {} {}...", "Fetching".yellow(), source.as_str());
-                let dest_path = install_base.join(key);
-                
-                // If a hash is locked, we want to check that out specifically to guarantee identical environments across machines
-                let target_commit = lockfile.get_commit(key).map(|s| s.as_str());
-                
-                let commit_hash = self.skill_fetcher.fetch(source, &dest_path, target_commit)?;
-                
-                // Run soft validation warnings
-                validate_skill_soft(&dest_path, key);
-
-                lockfile.set_commit(key.clone(), commit_hash); // Set it in case it wasn't there
-                count += 1;
+            let mut failed = Vec::new();
+            let mut invalid = Vec::new();
+            for key in &keys {
+                let dests = &dest_paths[key];
+                let result = match linked.remove(key.as_str()) {
+                    Some(result) => result,
+                    None => results.remove(key.as_str())
+                        .unwrap_or_else(|| Err(AppError::System(format!("Skill '{}' was not fetched", key))))
+                        .and_then(|entry| {
+                            placement.finish(&entry, &placement.fetch_path(key, &dests[0]), dests)?;
+                            Ok(entry)
+                        }),
+                }.and_then(|entry| {
+                    self.adapters.install(&manifest, dir, key)?;
+                    self.remove_excluded(&manifest, dir, key)?;
+                    Ok(entry)
+                });
+                match result {
+                    Ok(entry) => {
+                        println!("  {} {} ({})", "✔".green(), key, &entry.commit[..8.min(entry.commit.len())]);
+
+                        // Run soft validation warnings
+                        if !validate_skill_soft(&dests[0], key) {
+                            invalid.push(key.to_string());
+                        }
+
+                        lockfile.set_entry(key.to_string(), entry); // Set it in case it wasn't there
+                        count += 1;
+                    }
+                    Err(e) => {
+                        println!("  {} {}: {}", "✖".red(), key, e);
+                        failed.push(key.to_string());
+                    }
+                }
             }
-            
+
+            // Lock whatever did install, even if some skills failed
             let _ = self.lockfile_repo.save(&lockfile_path, &lockfile);
 
+            if !failed.is_empty() {
+                return Err(AppError::System(format!(
+                    "{} of {} skills failed to install: {}",
+                    failed.len(), keys.len(), failed.join(", ")
+                )));
+            }
+            if self.strict && !invalid.is_empty() {
+                return Err(AppError::Skill(format!(
+                    "{} skills have validation warnings (strict mode): {}",
+                    invalid.len(), invalid.join(", ")
+                )));
+            }
+
             if count > 0 {
                 println!("{} Installed {} skills", "✔".green().bold(), count);
             } else {
@@ -95,6 +209,16 @@ impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> InstallU
 
         Ok(())
     }
+
+    /// Removes copies of `key` left in targets its filter no longer selects.
+    fn remove_excluded(&self, manifest: &Manifest, dir: &Path, key: &str) -> Result<(), AppError> {
+        for path in self.adapters.installed_paths(manifest, dir, key, false)? {
+            if self.file_system.exists(&path) || self.file_system.is_symlink(&path) {
+                self.file_system.remove_dir_all(&path)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Extracts the raw skill name string from a source specifier.
@@ -16,8 +16,8 @@ impl<'a, M: ManifestRepository> ListUseCase<'a, M> {
         let manifest = self.manifest_repo.load(&manifest_path)?;
 
         let mut skills = Vec::new();
-        for (key, source) in &manifest.dependencies {
-            skills.push((key.clone(), source.as_str().to_string()));
+        for (key, dependency) in &manifest.dependencies {
+            skills.push((key.clone(), dependency.source.as_str().to_string()));
         }
 
         Ok(skills)
@@ -3,3 +3,12 @@ pub mod install;
 pub mod uninstall;
 pub mod list;
 pub mod update;
+pub mod versions;
+pub mod fetch;
+pub mod cache;
+pub mod config;
+pub mod parallel;
+pub mod batch;
+pub mod placement;
+pub mod targets;
+pub mod catalog;
@@ -0,0 +1,63 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Runs `task` over `items` on at most `jobs` worker threads.
+/// Results are returned in the same order as `items`, regardless of completion order.
+pub fn run_limited<T: Sync, R: Send>(items: &[T], jobs: usize, task: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let workers = jobs.clamp(1, items.len().max(1));
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..items.len()).map(|_| None).collect());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let idx = next.fetch_add(1, Ordering::SeqCst);
+                let Some(item) = items.get(idx) else { break };
+                let result = task(item);
+                results.lock().unwrap()[idx] = Some(result);
+            });
+        }
+    });
+
+    results.into_inner().unwrap()
+        .into_iter()
+        .map(|r| r.expect("every item is processed by a worker"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_keep_input_order() {
+        let items: Vec<u64> = (0..20).collect();
+        let results = run_limited(&items, 4, |n| {
+            std::thread::sleep(std::time::Duration::from_millis(20 - n));
+            n * 2
+        });
+        assert_eq!(results, items.iter().map(|n| n * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_never_exceeds_job_limit() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let items: Vec<u32> = (0..16).collect();
+
+        run_limited(&items, 3, |_| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(5));
+            running.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn test_zero_jobs_still_runs() {
+        assert_eq!(run_limited(&[1, 2], 0, |n| n + 1), vec![2, 3]);
+        assert!(run_limited(&Vec::<u8>::new(), 4, |n| *n).is_empty());
+    }
+}
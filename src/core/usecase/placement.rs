@@ -0,0 +1,82 @@
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use crate::core::domain::error::AppError;
+use crate::core::domain::lockfile::LockEntry;
+use crate::core::domain::manifest::InstallMode;
+use crate::core::port::file_system::FileSystem;
+use crate::core::port::skill_store::SkillStore;
+
+/// Decides where fetched skills are written and how they end up in the install directories.
+///
+/// In `copy` mode the fetcher writes straight to the first destination, which is copied to
+/// any others (one per target). In `symlink`/`hardlink` mode it writes into a staging area
+/// inside the global store; the folder is then moved to `<store>/<tree hash>` (unless already
+/// there) and linked into every destination.
+pub struct Placement<'a, F: FileSystem, T: SkillStore> {
+    mode: InstallMode,
+    file_system: &'a F,
+    store: &'a T,
+    staging: Option<TempDir>,
+}
+
+impl<'a, F: FileSystem, T: SkillStore> Placement<'a, F, T> {
+    pub fn new(mode: InstallMode, file_system: &'a F, store: &'a T) -> Result<Self, AppError> {
+        let staging = if mode.is_copy() {
+            None
+        } else {
+            Some(store.staging_dir()?)
+        };
+        Ok(Self { mode, file_system, store, staging })
+    }
+
+    /// Where the fetcher should write skill `key`, given the path it would use in copy mode.
+    pub fn fetch_path(&self, key: &str, default: &Path) -> PathBuf {
+        match &self.staging {
+            Some(staging) => staging.path().join(key),
+            None => default.to_path_buf(),
+        }
+    }
+
+    /// Links `dests` to stored content when the lock entry's tree is already in the store,
+    /// so nothing needs to be fetched. Always `false` in copy mode.
+    pub fn reuse(&self, entry: Option<&LockEntry>, dests: &[PathBuf]) -> Result<bool, AppError> {
+        let Some(tree) = entry.and_then(|e| e.tree.as_deref()) else { return Ok(false) };
+        if self.mode.is_copy() || !self.store.contains(tree) {
+            return Ok(false);
+        }
+        for dest in dests {
+            self.link(&self.store.path(tree), dest)?;
+        }
+        Ok(true)
+    }
+
+    /// Puts a skill fetched to `fetched` (see [`Placement::fetch_path`]) into place at each of `dests`.
+    pub fn finish(&self, entry: &LockEntry, fetched: &Path, dests: &[PathBuf]) -> Result<(), AppError> {
+        let Some((first, others)) = dests.split_first() else { return Ok(()) };
+        if self.mode.is_copy() {
+            if fetched != first {
+                self.file_system.move_dir(fetched, first)?;
+            }
+            for dest in others {
+                self.file_system.copy_dir(first, dest)?;
+            }
+            return Ok(());
+        }
+
+        let tree = entry.tree.as_deref()
+            .ok_or_else(|| AppError::System(format!("No content hash for {}, cannot add it to the store", first.display())))?;
+        // Another project may add the same content concurrently; the store keeps whichever copy came first
+        self.store.add(tree, fetched)?;
+        let stored = self.store.path(tree);
+        dests.iter().try_for_each(|dest| self.link(&stored, dest))
+    }
+
+    fn link(&self, stored: &Path, dest: &Path) -> Result<(), AppError> {
+        self.file_system.remove_dir_all(dest)?;
+        match self.mode {
+            InstallMode::Symlink => self.file_system.symlink_dir(stored, dest),
+            InstallMode::Hardlink => self.file_system.hardlink_tree(stored, dest),
+            InstallMode::Copy => unreachable!("copy mode never links"),
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use crate::core::domain::error::AppError;
+use crate::core::domain::manifest::Manifest;
+use crate::core::port::install_target::{InstallTarget, PlacedSkill};
+
+/// The install target adapters, by the `kind` manifest targets name them with.
+pub struct TargetAdapters<'a> {
+    adapters: BTreeMap<&'static str, &'a dyn InstallTarget>,
+}
+
+impl<'a> TargetAdapters<'a> {
+    pub fn new() -> Self {
+        Self { adapters: BTreeMap::new() }
+    }
+
+    pub fn register(mut self, kind: &'static str, adapter: &'a dyn InstallTarget) -> Self {
+        self.adapters.insert(kind, adapter);
+        self
+    }
+
+    /// Every target of the manifest must be of a registered kind.
+    pub fn check(&self, manifest: &Manifest) -> Result<(), AppError> {
+        for (name, target) in &manifest.targets {
+            if !self.adapters.contains_key(target.kind.as_str()) {
+                return Err(AppError::Manifest(format!(
+                    "Target '{}' has unknown kind '{}' (kinds: {})",
+                    name, target.kind, self.adapters.keys().cloned().collect::<Vec<_>>().join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Lets each target adapter write its files for skill `key`, whose folders are in place.
+    pub fn install(&self, manifest: &Manifest, dir: &Path, key: &str) -> Result<(), AppError> {
+        let dependency = manifest.dependencies.get(key)
+            .ok_or_else(|| AppError::System(format!("Skill '{}' not found in manifest", key)))?;
+        for target in manifest.target_dirs(dir, key).into_iter().filter(|t| t.selected) {
+            let folder = target.path.join(key);
+            let skill = PlacedSkill { key, dependency, folder: &folder, target_dir: &target.path, project_dir: dir };
+            self.adapter(target.kind)?.install(&skill)?;
+        }
+        Ok(())
+    }
+
+    /// Everything installed for skill `key` in targets it is not installed into (anything found is stale),
+    /// or in all targets when `all` is set.
+    pub fn installed_paths(&self, manifest: &Manifest, dir: &Path, key: &str, all: bool) -> Result<Vec<PathBuf>, AppError> {
+        let mut paths = Vec::new();
+        for target in manifest.target_dirs(dir, key).into_iter().filter(|t| all || !t.selected) {
+            paths.push(target.path.join(key));
+            paths.extend(self.adapter(target.kind)?.outputs(&target.path, key));
+        }
+        Ok(paths)
+    }
+
+    fn adapter(&self, kind: &str) -> Result<&'a dyn InstallTarget, AppError> {
+        self.adapters.get(kind).copied()
+            .ok_or_else(|| AppError::Manifest(format!("No install target adapter for kind '{}'", kind)))
+    }
+}
+
+impl Default for TargetAdapters<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Notes;
+
+    impl InstallTarget for Notes {
+        fn install(&self, _skill: &PlacedSkill) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn outputs(&self, target_dir: &Path, key: &str) -> Vec<PathBuf> {
+            vec![target_dir.join(format!("{}.txt", key))]
+        }
+    }
+
+    fn manifest(json: &str) -> Manifest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_installed_paths_include_adapter_outputs() {
+        let adapters = TargetAdapters::new().register("skills", &Notes).register("notes", &Notes);
+        let m = manifest(r#"{
+            "name": "p", "description": "",
+            "targets": {"a": "skills", "n": {"path": "notes", "kind": "notes"}},
+            "dependencies": {"x": {"source": "o/r/x", "targets": ["a"]}}
+        }"#);
+        assert!(adapters.check(&m).is_ok());
+        assert_eq!(adapters.installed_paths(&m, Path::new("/p"), "x", false).unwrap(), vec![PathBuf::from("/p/notes/x"), PathBuf::from("/p/notes/x.txt")]);
+        assert_eq!(adapters.installed_paths(&m, Path::new("/p"), "x", true).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_unknown_kind_is_rejected() {
+        let adapters = TargetAdapters::new().register("skills", &Notes);
+        let m = manifest(r#"{"name": "p", "description": "", "targets": {"n": {"path": "notes", "kind": "notes"}}}"#);
+        assert!(adapters.check(&m).is_err());
+    }
+}
@@ -4,16 +4,18 @@ use crate::core::domain::error::AppError;
 use crate::core::port::manifest_repository::ManifestRepository;
 use crate::core::port::file_system::FileSystem;
 use crate::core::port::lockfile_repository::LockfileRepository;
+use crate::core::usecase::targets::TargetAdapters;
 
 pub struct UninstallUseCase<'a, M: ManifestRepository, F: FileSystem, L: LockfileRepository> {
     manifest_repo: &'a M,
     file_system: &'a F,
     lockfile_repo: &'a L,
+    adapters: &'a TargetAdapters<'a>,
 }
 
 impl<'a, M: ManifestRepository, F: FileSystem, L: LockfileRepository> UninstallUseCase<'a, M, F, L> {
-    pub fn new(manifest_repo: &'a M, file_system: &'a F, lockfile_repo: &'a L) -> Self {
-        Self { manifest_repo, file_system, lockfile_repo }
+    pub fn new(manifest_repo: &'a M, file_system: &'a F, lockfile_repo: &'a L, adapters: &'a TargetAdapters<'a>) -> Self {
+        Self { manifest_repo, file_system, lockfile_repo, adapters }
     }
 
     pub fn execute(&self, dir: &Path, skill_key: &str) -> Result<(), AppError> {
@@ -35,11 +37,12 @@ impl<'a, M: ManifestRepository, F: FileSystem, L: LockfileRepository> UninstallU
 
         println!("{} {}...", "=> Uninstalling".cyan().bold(), skill_key);
 
-        let install_base = manifest.install_dir.clone().unwrap_or_else(|| dir.to_path_buf());
-        let dest_path = install_base.join(skill_key);
-
-        if self.file_system.exists(&dest_path) {
-            self.file_system.remove_dir_all(&dest_path)?;
+        // Every target, including ones the skill's filter excludes, in case a stale copy is left there
+        let dest_paths = self.adapters.installed_paths(&manifest, dir, skill_key, true)?;
+        for dest_path in &dest_paths {
+            if self.file_system.exists(dest_path) || self.file_system.is_symlink(dest_path) {
+                self.file_system.remove_dir_all(dest_path)?;
+            }
         }
 
         manifest.remove_dependency(skill_key);
@@ -1,20 +1,56 @@
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use colored::Colorize;
 use crate::core::domain::error::AppError;
+use crate::core::domain::dependency::{Dependency, UpdatePolicy};
+use crate::core::domain::skill::{SkillSource, SourceRef};
+use crate::core::domain::lockfile::LockEntry;
+use crate::core::domain::version::newest_release_tag;
 use crate::core::port::manifest_repository::ManifestRepository;
 use crate::core::port::skill_fetcher::SkillFetcher;
 use crate::core::port::lockfile_repository::LockfileRepository;
+use crate::core::port::file_system::FileSystem;
+use crate::core::port::skill_store::SkillStore;
 use crate::core::domain::validation::validate_skill_soft;
+use crate::core::usecase::parallel::run_limited;
+use crate::core::usecase::batch::FetchPlan;
+use crate::core::usecase::placement::Placement;
+use crate::core::usecase::targets::TargetAdapters;
 
-pub struct UpdateUseCase<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> {
+pub struct UpdateUseCase<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository, F: FileSystem, T: SkillStore> {
     manifest_repo: &'a M,
     skill_fetcher: &'a S,
     lockfile_repo: &'a L,
+    file_system: &'a F,
+    store: &'a T,
+    adapters: &'a TargetAdapters<'a>,
+    jobs: usize,
+    keep_unchanged: bool,
+    strict: bool,
 }
 
-impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> UpdateUseCase<'a, M, S, L> {
-    pub fn new(manifest_repo: &'a M, skill_fetcher: &'a S, lockfile_repo: &'a L) -> Self {
-        Self { manifest_repo, skill_fetcher, lockfile_repo }
+impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository, F: FileSystem, T: SkillStore> UpdateUseCase<'a, M, S, L, F, T> {
+    pub fn new(manifest_repo: &'a M, skill_fetcher: &'a S, lockfile_repo: &'a L, file_system: &'a F, store: &'a T, adapters: &'a TargetAdapters<'a>) -> Self {
+        Self { manifest_repo, skill_fetcher, lockfile_repo, file_system, store, adapters, jobs: 1, keep_unchanged: false, strict: false }
+    }
+
+    /// Sets how many skills may be checked and fetched concurrently.
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    /// When a skill's folder is identical at the new commit, keep the old commit locked
+    /// instead of advancing it.
+    pub fn with_keep_unchanged(mut self, keep_unchanged: bool) -> Self {
+        self.keep_unchanged = keep_unchanged;
+        self
+    }
+
+    /// Treats skill validation warnings as errors.
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
     }
 
     pub fn execute(&self, dir: &Path, skill_key_opt: Option<&str>) -> Result<(), AppError> {
@@ -30,9 +66,11 @@ impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> UpdateUs
             )
         });
 
-        let install_base = manifest.install_dir.clone().unwrap_or_else(|| dir.to_path_buf());
+        manifest.check_targets()?;
+        self.adapters.check(&manifest)?;
+        let placement = Placement::new(manifest.install_mode, self.file_system, self.store)?;
 
-        let keys_to_update: Vec<String> = if let Some(key) = skill_key_opt {
+        let mut keys_to_update: Vec<String> = if let Some(key) = skill_key_opt {
             if !manifest.dependencies.contains_key(key) {
                 return Err(AppError::System(format!("Skill '{}' not found in manifest", key)));
             }
@@ -40,6 +78,18 @@ impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> UpdateUs
         } else {
             manifest.dependencies.keys().cloned().collect()
         };
+        keys_to_update.sort();
+
+        // Manual skills only move when the user names them explicitly
+        if skill_key_opt.is_none() {
+            keys_to_update.retain(|key| {
+                let manual = manifest.dependencies[key].update == UpdatePolicy::Manual;
+                if manual {
+                    println!("  {} {} has update policy 'manual', skipping.", "ℹ".blue(), key);
+                }
+                !manual
+            });
+        }
 
         if keys_to_update.is_empty() {
             println!("{} Nothing to update.", "ℹ".blue().bold());
@@ -48,34 +98,288 @@ impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> UpdateUs
 
         println!("{} Checking {} skills for updates...", "=>".cyan().bold(), keys_to_update.len());
 
-        for key in keys_to_update {
-            if let Some(source) = manifest.dependencies.get(&key) {
-                println!("  {} {}...", "Checking".yellow(), key);
-                
-                let dest_path = install_base.join(&key);
-                let current_hash = lockfile.get_commit(&key).cloned().unwrap_or_else(|| "unknown".to_string());
-                
-                // Cheaply resolve the remote hash via ls-remote before doing expensive fetch+copy
-                let remote_hash = self.skill_fetcher.resolve_remote_hash(source)?;
-                
-                if current_hash == remote_hash && current_hash != "unknown" {
+        // 1. Cheaply resolve remote hashes before doing expensive fetch+copy, with one ls-remote per repository
+        let mut by_repository: BTreeMap<&str, Vec<&String>> = BTreeMap::new();
+        for key in &keys_to_update {
+            by_repository.entry(manifest.dependencies[key].source.repository()).or_default().push(key);
+        }
+        let repository_groups: Vec<Vec<&String>> = by_repository.into_values().collect();
+
+        // Only the fetcher is shared with the worker threads
+        let fetcher = self.skill_fetcher;
+        for keys in &repository_groups {
+            println!("  {} {}...", "Checking".yellow(), keys.iter().map(|k| k.as_str()).collect::<Vec<_>>().join(", "));
+        }
+        let resolved = run_limited(&repository_groups, self.jobs, |keys| {
+            let dependencies: Vec<&Dependency> = keys.iter().map(|k| &manifest.dependencies[k.as_str()]).collect();
+            resolve_targets(fetcher, &dependencies)
+        });
+        let mut targets: HashMap<&String, Result<Option<UpdateTarget>, AppError>> = repository_groups.iter()
+            .flatten()
+            .copied()
+            .zip(resolved.into_iter().flatten())
+            .collect();
+
+        // 2. Compare the skill folder's tree hash at the new commit: a monorepo commit that
+        // does not touch the folder only advances the lock, without rewriting any files.
+        // A skill missing from one of its install paths is placed again instead.
+        let mut moved: BTreeMap<(&str, &str), Vec<&String>> = BTreeMap::new();
+        for key in &keys_to_update {
+            if let Some(Ok(Some(target))) = targets.get(key) {
+                if lockfile.get_commit(key) != Some(&target.commit) {
+                    moved.entry((manifest.dependencies[key].source.repository(), target.commit.as_str())).or_default().push(key);
+                }
+            }
+        }
+        let moved: Vec<((&str, &str), Vec<&String>)> = moved.into_iter().collect();
+        let compared = run_limited(&moved, self.jobs, |((_, commit), keys)| {
+            let sources: Vec<_> = keys.iter().map(|k| manifest.dependencies[k.as_str()].source.clone()).collect();
+            let new_trees = fetcher.resolve_trees(&sources, commit).unwrap_or_default();
+            keys.iter().enumerate().map(|(i, key)| {
+                let new_tree = new_trees.get(i).cloned().flatten()?;
+                let old_tree = old_tree(fetcher, lockfile.get_entry(key)?, &sources[i])?;
+                (old_tree == new_tree).then(|| LockEntry::new(commit.to_string(), new_tree))
+            }).collect::<Vec<_>>()
+        });
+        let mut unchanged: HashMap<&String, LockEntry> = moved.iter()
+            .flat_map(|(_, keys)| keys.iter().copied())
+            .zip(compared.into_iter().flatten())
+            .filter_map(|(key, entry)| entry.map(|e| (key, e)))
+            .filter(|(key, _)| manifest.install_paths(dir, key).iter().all(|path| self.file_system.exists(path)))
+            .collect();
+
+        // 3. Only fetch and overwrite files when the content actually changed.
+        // Targets are pinned by commit, so skills of one repository moving to the same commit share a checkout.
+        let mut plan = FetchPlan::new();
+        for ((_, commit), keys) in &moved {
+            for key in keys.iter().filter(|k| !unchanged.contains_key(*k)) {
+                let fetch_path = placement.fetch_path(key, &manifest.install_paths(dir, key)[0]);
+                plan.add(key, &manifest.dependencies[key.as_str()].source, fetch_path, Some(commit));
+            }
+        }
+        let mut fetched = plan.run(fetcher, self.jobs);
+
+        let results = keys_to_update.iter().map(|key| {
+            match targets.remove(key).expect("every key was resolved") {
+                Err(e) => Err(e),
+                Ok(None) => Ok(UpdateOutcome::NoReleaseTags),
+                Ok(Some(target)) => {
+                    if let Some(entry) = unchanged.remove(key) {
+                        return Ok(UpdateOutcome::ContentUnchanged { entry });
+                    }
+                    match fetched.remove(key.as_str()) {
+                        None => Ok(UpdateOutcome::UpToDate),
+                        Some(result) => result.and_then(|entry| {
+                            let dest_paths = manifest.install_paths(dir, key);
+                            placement.finish(&entry, &placement.fetch_path(key, &dest_paths[0]), &dest_paths)?;
+                            self.adapters.install(&manifest, dir, key)?;
+                            Ok(UpdateOutcome::Updated { entry, tag: target.tag })
+                        }),
+                    }
+                }
+            }
+        }).collect::<Vec<_>>();
+
+        // Report sequentially so validation warnings stay next to the skill they belong to
+        let mut failed = Vec::new();
+        let mut invalid = Vec::new();
+        for (key, result) in keys_to_update.iter().zip(results) {
+            match result {
+                Ok(UpdateOutcome::UpToDate) => {
                     println!("  {} {} is already up to date.", "✔".green(), key);
-                } else {
-                    // Only fetch and overwrite files when the hash actually changed
-                    let new_hash = self.skill_fetcher.fetch(source, &dest_path, None)?;
-                    
+                }
+                Ok(UpdateOutcome::NoReleaseTags) => {
+                    println!("  {} No release tags found for {}, skipping.", "⚠️".yellow(), key);
+                }
+                Ok(UpdateOutcome::ContentUnchanged { entry }) => {
+                    let current_hash = lockfile.get_commit(key).cloned().unwrap_or_default();
+                    if self.keep_unchanged {
+                        println!("  {} {} has no content change, keeping {}.", "✔".green(), key, &current_hash[..8.min(current_hash.len())]);
+                    } else {
+                        println!("  {} {} has no content change, lock advanced ({} -> {}).", "✔".green(), key, &current_hash[..8.min(current_hash.len())], &entry.commit[..8.min(entry.commit.len())]);
+                        lockfile.set_entry(key.clone(), entry);
+                    }
+                }
+                Ok(UpdateOutcome::Updated { entry, tag }) => {
                     // Run soft validation
-                    validate_skill_soft(&dest_path, &key);
-                    
-                    println!("  {} {} updated ({} -> {}).", "✔".green(), key, &current_hash[..8.min(current_hash.len())], &new_hash[..8.min(new_hash.len())]);
-                    lockfile.set_commit(key.clone(), new_hash);
+                    if !validate_skill_soft(&manifest.install_paths(dir, key)[0], key) {
+                        invalid.push(key.clone());
+                    }
+
+                    let current_hash = lockfile.get_commit(key).cloned().unwrap_or_else(|| "unknown".to_string());
+                    let via = tag.map(|t| format!(" [{}]", t)).unwrap_or_default();
+                    println!("  {} {} updated ({} -> {}){}.", "✔".green(), key, &current_hash[..8.min(current_hash.len())], &entry.commit[..8.min(entry.commit.len())], via);
+                    lockfile.set_entry(key.clone(), entry);
+                }
+                Err(e) => {
+                    println!("  {} {}: {}", "✖".red(), key, e);
+                    failed.push(key.clone());
                 }
             }
         }
 
+        if !failed.is_empty() {
+            let _ = self.lockfile_repo.save(&lockfile_path, &lockfile);
+            return Err(AppError::System(format!(
+                "{} of {} skills failed to update: {}",
+                failed.len(), keys_to_update.len(), failed.join(", ")
+            )));
+        }
+
         let _ = self.lockfile_repo.save(&lockfile_path, &lockfile);
+        if self.strict && !invalid.is_empty() {
+            return Err(AppError::Skill(format!(
+                "{} skills have validation warnings (strict mode): {}",
+                invalid.len(), invalid.join(", ")
+            )));
+        }
         println!("{} Update complete.", "✔".green().bold());
 
         Ok(())
     }
+
+    /// Points an installed skill at a different ref (`update <skill> --to <ref>`).
+    /// The skill is fetched into a staging directory first, so a bad ref or path
+    /// leaves the installed files, manifest and lockfile untouched.
+    pub fn retarget(&self, dir: &Path, skill_key: &str, new_ref: &SourceRef) -> Result<(), AppError> {
+        let manifest_path = dir.join("skills.arsync");
+        let mut manifest = self.manifest_repo.load(&manifest_path)?;
+
+        let lockfile_path = dir.join("skills-lock.arsync");
+        let mut lockfile = self.lockfile_repo.load(&lockfile_path).unwrap_or_else(|_| {
+            crate::core::domain::lockfile::Lockfile::new(
+                manifest.name.clone(),
+                manifest.description.clone(),
+                manifest.install_dir.clone()
+            )
+        });
+
+        manifest.check_targets()?;
+        self.adapters.check(&manifest)?;
+        let dest_paths = manifest.install_paths(dir, skill_key);
+
+        let dependency = manifest.dependencies.get_mut(skill_key)
+            .ok_or_else(|| AppError::System(format!("Skill '{}' not found in manifest", skill_key)))?;
+        let new_source = dependency.source.with_ref(new_ref);
+
+        println!("{} {} ({} -> {})...", "=> Switching".cyan().bold(), skill_key, dependency.source.as_str(), new_source.as_str());
+
+        let placement = Placement::new(manifest.install_mode, self.file_system, self.store)?;
+        let staging = tempfile::tempdir().map_err(|e| AppError::System(format!("Failed to create temp dir: {}", e)))?;
+        let staged_path = placement.fetch_path(skill_key, &staging.path().join(skill_key));
+        let entry = self.skill_fetcher.fetch(&new_source, &staged_path, None)?;
+
+        // In strict mode an invalid ref is rejected before anything is replaced
+        if !validate_skill_soft(&staged_path, skill_key) && self.strict {
+            return Err(AppError::Skill(format!("{} has validation warnings at {} (strict mode), not switched", skill_key, new_source.as_str())));
+        }
+
+        placement.finish(&entry, &staged_path, &dest_paths)?;
+
+        dependency.source = new_source.clone();
+        self.adapters.install(&manifest, dir, skill_key)?;
+        self.manifest_repo.save(&manifest_path, &manifest)?;
+
+        println!("{} {} now tracks {} ({}).", "✔".green().bold(), skill_key, new_source.as_str(), &entry.commit[..8.min(entry.commit.len())]);
+
+        lockfile.set_entry(skill_key.to_string(), entry);
+        let _ = self.lockfile_repo.save(&lockfile_path, &lockfile);
+
+        Ok(())
+    }
+}
+
+/// Resolves the commit each dependency of one repository should move to under its update policy,
+/// using a single ref listing for `tags` dependencies and a single ls-remote for the rest.
+/// `None` means a `tags` dependency has no release tag to move to.
+fn resolve_targets<S: SkillFetcher>(fetcher: &S, dependencies: &[&Dependency]) -> Vec<Result<Option<UpdateTarget>, AppError>> {
+    let (tagged, following): (Vec<&Dependency>, Vec<&Dependency>) = dependencies.iter()
+        .partition(|d| d.update == UpdatePolicy::Tags);
+
+    let newest_tag = tagged.first().map(|d| {
+        fetcher.list_refs(&d.source).map(|refs| newest_release_tag(&refs).map(|r| UpdateTarget {
+            commit: r.commit.clone(),
+            tag: Some(r.name.clone()),
+        }))
+    });
+
+    let following_sources: Vec<_> = following.iter().map(|d| d.source.clone()).collect();
+    let mut following_hashes = fetcher.resolve_remote_hashes(&following_sources).map(Vec::into_iter);
+
+    dependencies.iter().map(|d| {
+        if d.update == UpdatePolicy::Tags {
+            match newest_tag.as_ref().expect("a tags dependency exists") {
+                Ok(target) => Ok(target.clone()),
+                Err(e) => Err(e.clone()),
+            }
+        } else {
+            match following_hashes.as_mut() {
+                Ok(hashes) => hashes.next()
+                    .map(|commit| Some(UpdateTarget { commit, tag: None }))
+                    .ok_or_else(|| AppError::System(format!("Could not resolve {}", d.source.as_str()))),
+                Err(e) => Err(e.clone()),
+            }
+        }
+    }).collect()
+}
+
+/// The folder tree hash a skill is locked to. Older lock entries only recorded the
+/// commit, so the tree is then looked up in the cache at that commit.
+fn old_tree<S: SkillFetcher>(fetcher: &S, entry: &LockEntry, source: &SkillSource) -> Option<String> {
+    match &entry.tree {
+        Some(tree) => Some(tree.clone()),
+        None => fetcher.resolve_trees(std::slice::from_ref(source), &entry.commit).ok()?.pop().flatten(),
+    }
+}
+
+#[derive(Clone)]
+struct UpdateTarget {
+    commit: String,
+    tag: Option<String>,
+}
+
+enum UpdateOutcome {
+    UpToDate,
+    NoReleaseTags,
+    ContentUnchanged { entry: LockEntry },
+    Updated { entry: LockEntry, tag: Option<String> },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+    use crate::core::domain::version::RemoteRef;
+
+    /// A host that refuses every request for lack of credentials.
+    struct RefusingFetcher;
+
+    impl SkillFetcher for RefusingFetcher {
+        fn fetch_batch(&self, _items: &[(SkillSource, PathBuf)], _target_commit: Option<&str>) -> Result<Vec<Result<LockEntry, AppError>>, AppError> {
+            Err(AppError::Auth("github.com needs credentials".to_string()))
+        }
+
+        fn resolve_trees(&self, _sources: &[SkillSource], _commit: &str) -> Result<Vec<Option<String>>, AppError> {
+            Err(AppError::Auth("github.com needs credentials".to_string()))
+        }
+
+        fn resolve_remote_hashes(&self, _sources: &[SkillSource]) -> Result<Vec<String>, AppError> {
+            Err(AppError::Auth("github.com needs credentials".to_string()))
+        }
+
+        fn list_refs(&self, _source: &SkillSource) -> Result<Vec<RemoteRef>, AppError> {
+            Err(AppError::Auth("github.com needs credentials".to_string()))
+        }
+    }
+
+    #[test]
+    fn test_auth_failures_keep_their_variant() {
+        let following = Dependency::new(SkillSource::new("org/private/a"));
+        let mut tagged = Dependency::new(SkillSource::new("org/private/b"));
+        tagged.update = UpdatePolicy::Tags;
+
+        let targets = resolve_targets(&RefusingFetcher, &[&following, &tagged]);
+        assert_eq!(targets.len(), 2);
+        assert!(targets.iter().all(|t| matches!(t, Err(AppError::Auth(_)))));
+    }
 }
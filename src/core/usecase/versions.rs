@@ -0,0 +1,46 @@
+use std::path::Path;
+use crate::core::domain::error::AppError;
+use crate::core::domain::skill::SkillSource;
+use crate::core::domain::version::{sort_refs, RemoteRef};
+use crate::core::port::manifest_repository::ManifestRepository;
+use crate::core::port::skill_fetcher::SkillFetcher;
+use crate::core::port::lockfile_repository::LockfileRepository;
+
+/// The refs available for a source, plus the commit currently locked (if it is installed).
+pub struct VersionsReport {
+    pub source: SkillSource,
+    pub locked_commit: Option<String>,
+    pub refs: Vec<RemoteRef>,
+}
+
+pub struct VersionsUseCase<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> {
+    manifest_repo: &'a M,
+    skill_fetcher: &'a S,
+    lockfile_repo: &'a L,
+}
+
+impl<'a, M: ManifestRepository, S: SkillFetcher, L: LockfileRepository> VersionsUseCase<'a, M, S, L> {
+    pub fn new(manifest_repo: &'a M, skill_fetcher: &'a S, lockfile_repo: &'a L) -> Self {
+        Self { manifest_repo, skill_fetcher, lockfile_repo }
+    }
+
+    /// `target` is either a skill key from the manifest or a source specifier.
+    pub fn execute(&self, dir: &Path, target: &str) -> Result<VersionsReport, AppError> {
+        let manifest = self.manifest_repo.load(&dir.join("skills.arsync")).ok();
+
+        let (source, locked_commit) = match manifest.as_ref().and_then(|m| m.dependencies.get(target)) {
+            Some(dependency) => {
+                let locked = self.lockfile_repo.load(&dir.join("skills-lock.arsync")).ok()
+                    .and_then(|l| l.get_commit(target).cloned());
+                (dependency.source.clone(), locked)
+            }
+            None if target.contains('/') => (SkillSource::new(target), None),
+            None => return Err(AppError::System(format!("Skill '{}' not found in manifest", target))),
+        };
+
+        let mut refs = self.skill_fetcher.list_refs(&source)?;
+        sort_refs(&mut refs);
+
+        Ok(VersionsReport { source, locked_commit, refs })
+    }
+}
@@ -0,0 +1,96 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use crate::core::domain::config::Settings;
+use crate::core::domain::error::AppError;
+use crate::core::port::config_repository::ConfigRepository;
+
+/// Reads and writes `config.toml` files.
+pub struct TomlConfigRepository;
+
+impl TomlConfigRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TomlConfigRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigRepository for TomlConfigRepository {
+    fn load(&self, path: &Path) -> Result<Settings, AppError> {
+        match fs::read_to_string(path) {
+            Ok(content) => toml::from_str(&content)
+                .map_err(|e| AppError::Config(format!("{}: {}", path.display(), e.message()))),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Settings::default()),
+            Err(e) => Err(AppError::Io(e)),
+        }
+    }
+
+    fn save(&self, path: &Path, settings: &Settings) -> Result<(), AppError> {
+        let content = toml::to_string(settings).map_err(|e| AppError::Config(e.to_string()))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(AppError::Io)?;
+        }
+        write_private(path, &content).map_err(AppError::Io)
+    }
+}
+
+/// Writes `content` readable by the owner only, since the file may hold access tokens.
+/// An existing file is tightened before anything is written to it.
+#[cfg(unix)]
+fn write_private(path: &Path, content: &str) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    if path.exists() {
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?
+        .write_all(content.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private(path: &Path, content: &str) -> io::Result<()> {
+    fs::write(path, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables_are_named_like_their_keys() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        fs::write(&path, "[token]\n\"github.com\" = \"secret\"\n\n[rewrite]\n\"https://github.com/\" = \"https://git.internal/\"\n").unwrap();
+
+        let settings = TomlConfigRepository::new().load(&path).unwrap();
+        assert_eq!(settings.get("token.github.com").unwrap().as_deref(), Some("secret"));
+        assert_eq!(settings.get("rewrite.https://github.com/").unwrap().as_deref(), Some("https://git.internal/"));
+        assert!(toml::to_string(&settings).unwrap().contains("[token]"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_saved_config_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("config.toml");
+        let repo = TomlConfigRepository::new();
+        let mut settings = Settings::default();
+        settings.set("token.github.com", "secret").unwrap();
+
+        repo.save(&path, &settings).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+        repo.save(&path, &settings).unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        assert_eq!(repo.load(&path).unwrap(), settings);
+    }
+}
@@ -1,6 +1,6 @@
 use std::fs;
+use std::io;
 use std::path::Path;
-use fs_extra::dir::{copy, CopyOptions};
 use crate::core::port::file_system::FileSystem;
 use crate::core::domain::error::AppError;
 
@@ -12,32 +12,146 @@ impl LocalFileSystem {
     }
 }
 
+impl Default for LocalFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FileSystem for LocalFileSystem {
     fn exists(&self, path: &Path) -> bool {
         path.exists()
     }
 
     fn remove_dir_all(&self, path: &Path) -> Result<(), AppError> {
-        if path.exists() {
-            fs::remove_dir_all(path).map_err(AppError::Io)?;
-        }
-        Ok(())
+        remove_path(path).map_err(AppError::Io)
     }
 
     fn move_dir(&self, src: &Path, dest: &Path) -> Result<(), AppError> {
-        if dest.exists() {
-            fs::remove_dir_all(dest).map_err(AppError::Io)?;
-        }
+        remove_path(dest).map_err(AppError::Io)?;
         if let Some(parent) = dest.parent() {
             fs::create_dir_all(parent).map_err(AppError::Io)?;
         }
-        
-        let mut options = CopyOptions::new();
-        options.copy_inside = true;
-        
-        copy(src, dest, &options).map_err(|e| AppError::System(format!("Failed to copy directory: {}", e)))?;
+
+        // A rename is atomic and keeps everything as-is; across filesystems fall back to copying
+        if fs::rename(src, dest).is_ok() {
+            return Ok(());
+        }
+
+        copy_tree(src, dest).map_err(|e| AppError::System(format!("Failed to copy directory: {}", e)))?;
         fs::remove_dir_all(src).map_err(AppError::Io)?;
-        
+
         Ok(())
     }
+
+    fn copy_dir(&self, src: &Path, dest: &Path) -> Result<(), AppError> {
+        remove_path(dest).map_err(AppError::Io)?;
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(AppError::Io)?;
+        }
+        copy_tree(src, dest).map_err(|e| AppError::System(format!("Failed to copy directory: {}", e)))
+    }
+
+    fn symlink_dir(&self, target: &Path, link: &Path) -> Result<(), AppError> {
+        if let Some(parent) = link.parent() {
+            fs::create_dir_all(parent).map_err(AppError::Io)?;
+        }
+        symlink_dir(target, link)
+            .map_err(|e| AppError::System(format!("Failed to link {} -> {}: {}", link.display(), target.display(), e)))
+    }
+
+    fn hardlink_tree(&self, src: &Path, dest: &Path) -> Result<(), AppError> {
+        hardlink_tree(src, dest)
+            .map_err(|e| AppError::System(format!("Failed to hard-link {} into {}: {}", src.display(), dest.display(), e)))
+    }
+
+    fn is_symlink(&self, path: &Path) -> bool {
+        path.symlink_metadata().map(|m| m.file_type().is_symlink()).unwrap_or(false)
+    }
+
+    fn read_to_string(&self, path: &Path) -> Result<String, AppError> {
+        fs::read_to_string(path).map_err(AppError::Io)
+    }
+
+    fn write_file(&self, path: &Path, contents: &str) -> Result<(), AppError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(AppError::Io)?;
+        }
+        fs::write(path, contents).map_err(AppError::Io)
+    }
+}
+
+/// Removes a directory tree, or just the link when `path` is a symlink (even a dangling one),
+/// so content shared through the global store is never deleted through a project's link.
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    match path.symlink_metadata() {
+        Ok(meta) if meta.file_type().is_symlink() => fs::remove_file(path).or_else(|_| fs::remove_dir(path)),
+        Ok(meta) if meta.is_dir() => fs::remove_dir_all(path),
+        Ok(_) => fs::remove_file(path),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+fn hardlink_tree(src: &Path, dest: &Path) -> io::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = dest.join(entry.file_name());
+
+        if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else if file_type.is_dir() {
+            hardlink_tree(&entry.path(), &target)?;
+        } else {
+            fs::hard_link(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Recursively copies `src` into a new directory `dest`, recreating symlinks
+/// instead of following them. File permissions (e.g. executable bits) are kept.
+pub fn copy_tree(src: &Path, dest: &Path) -> io::Result<()> {
+    fs::create_dir_all(dest)?;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = dest.join(entry.file_name());
+
+        if file_type.is_symlink() {
+            copy_symlink(&entry.path(), &target)?;
+        } else if file_type.is_dir() {
+            copy_tree(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(windows)]
+fn symlink_dir(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_dir(target, link)
+}
+
+#[cfg(unix)]
+fn copy_symlink(src: &Path, dest: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(fs::read_link(src)?, dest)
+}
+
+#[cfg(windows)]
+fn copy_symlink(src: &Path, dest: &Path) -> io::Result<()> {
+    let link = fs::read_link(src)?;
+    if fs::metadata(src).map(|m| m.is_dir()).unwrap_or(false) {
+        std::os::windows::fs::symlink_dir(link, dest)
+    } else {
+        std::os::windows::fs::symlink_file(link, dest)
+    }
 }
@@ -0,0 +1,64 @@
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+use crate::core::domain::error::AppError;
+
+/// How long to wait for another arsync process before giving up.
+pub const LOCK_TIMEOUT: Duration = Duration::from_secs(300);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// An advisory lock on a file, shared between arsync processes and released when dropped.
+///
+/// The holder writes its pid into the file, so a waiting process can say who it is waiting for.
+pub struct FileLock {
+    _file: File,
+}
+
+impl FileLock {
+    /// Blocks until the lock at `path` is free, for at most `timeout`. `what` names the
+    /// locked resource in messages (e.g. "project", "cache of owner/repo").
+    pub fn acquire(path: &Path, what: &str, timeout: Duration) -> Result<Self, AppError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(AppError::Io)?;
+        }
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)
+            .map_err(AppError::Io)?;
+
+        let started = Instant::now();
+        let mut announced = false;
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) => {
+                    let holder = holder(path);
+                    if started.elapsed() >= timeout {
+                        return Err(AppError::System(format!(
+                            "Timed out after {}s waiting for the lock on {} held by {}",
+                            timeout.as_secs(), what, holder
+                        )));
+                    }
+                    if !announced {
+                        eprintln!("Waiting for the lock on {} held by {}...", what, holder);
+                        announced = true;
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                }
+                Err(TryLockError::Error(e)) => return Err(AppError::Io(e)),
+            }
+        }
+
+        file.set_len(0).map_err(AppError::Io)?;
+        write!(file, "{}", std::process::id()).map_err(AppError::Io)?;
+        Ok(Self { _file: file })
+    }
+}
+
+fn holder(path: &Path) -> String {
+    match fs::read_to_string(path).ok().map(|s| s.trim().to_string()) {
+        Some(pid) if !pid.is_empty() => format!("pid {}", pid),
+        _ => "another process".to_string(),
+    }
+}
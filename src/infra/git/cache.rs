@@ -0,0 +1,274 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::core::domain::cache::CachedRepository;
+use crate::core::domain::error::AppError;
+use crate::core::port::repository_cache::RepositoryCache;
+use crate::infra::fs::lock::{FileLock, LOCK_TIMEOUT};
+use crate::infra::git::process::stderr_tail;
+
+const INDEX_FILE: &str = "index.json";
+/// Lock files live apart from the repositories so that removing or re-cloning one never deletes a held lock.
+const LOCKS_DIR: &str = ".locks";
+/// Written into a bare repository once its clone finished, so later uses only need the cheap check.
+const COMPLETE_MARKER: &str = "arsync-complete";
+
+/// Small bookkeeping file next to the cached repositories, recording when each was last used.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    #[serde(default)]
+    repositories: BTreeMap<String, IndexEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct IndexEntry {
+    #[serde(rename = "last-used")]
+    last_used: u64,
+}
+
+impl CacheIndex {
+    /// A missing or unreadable index is treated as empty: it is only a usage hint.
+    fn load(root: &Path) -> Self {
+        fs::read_to_string(root.join(INDEX_FILE)).ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, root: &Path) -> Result<(), AppError> {
+        fs::create_dir_all(root).map_err(AppError::Io)?;
+        // Write then rename, so a reader never sees a half-written index
+        let tmp = root.join(format!("{}.tmp", INDEX_FILE));
+        fs::write(&tmp, serde_json::to_string_pretty(self)?).map_err(AppError::Io)?;
+        fs::rename(&tmp, root.join(INDEX_FILE)).map_err(AppError::Io)
+    }
+}
+
+/// Health check run before every use of a cached repository: the clone completed and `HEAD` resolves.
+/// A cache without the completion marker (e.g. cloned by an older version) must also pass a
+/// connectivity check once before it is trusted; passing it writes the marker.
+pub fn is_usable(dir: &Path) -> bool {
+    check_health(dir, true)
+}
+
+/// The same check for the read-only shared cache, which is never written to.
+pub fn is_usable_read_only(dir: &Path) -> bool {
+    check_health(dir, false)
+}
+
+fn check_health(dir: &Path, remember: bool) -> bool {
+    let git_ok = |args: &[&str]| Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success());
+
+    if !git_ok(&["rev-parse", "--verify", "--quiet", "HEAD^{commit}"]) {
+        return false;
+    }
+    if dir.join(COMPLETE_MARKER).exists() {
+        return true;
+    }
+    if !git_ok(&["fsck", "--connectivity-only", "--no-progress", "--no-dangling"]) {
+        return false;
+    }
+    if remember {
+        // Only an optimisation; the cache is usable even when the marker cannot be written
+        let _ = mark_complete(dir);
+    }
+    true
+}
+
+pub fn mark_complete(dir: &Path) -> std::io::Result<()> {
+    fs::write(dir.join(COMPLETE_MARKER), "")
+}
+
+/// Takes the inter-process lock on one cached repository, held while it is fetched, read or removed.
+pub fn lock_repository(root: &Path, repository: &str) -> Result<FileLock, AppError> {
+    let path = root.join(LOCKS_DIR).join(format!("{}.lock", repository));
+    FileLock::acquire(&path, &format!("the cache of {}", repository), LOCK_TIMEOUT)
+}
+
+/// Takes the inter-process lock on the project in `dir`. It is kept with the cache's locks, keyed by
+/// the project's absolute path, so nothing shows up in the project itself (e.g. in `git status`).
+pub fn lock_project(root: &Path, dir: &Path) -> Result<FileLock, AppError> {
+    FileLock::acquire(&project_lock_path(root, dir), "this project", LOCK_TIMEOUT)
+}
+
+/// `.locks/.projects/<dir name>-<hash>.lock`; owners cannot start with a dot, so this never meets a repository lock.
+fn project_lock_path(root: &Path, dir: &Path) -> PathBuf {
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let name = dir.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    root.join(LOCKS_DIR).join(".projects").join(format!("{}-{:016x}.lock", name, fnv1a(dir.as_os_str().as_encoded_bytes())))
+}
+
+/// FNV-1a, a hash that never changes between builds, so every arsync version picks the same lock for a project.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+fn lock_index(root: &Path) -> Result<FileLock, AppError> {
+    FileLock::acquire(&root.join(LOCKS_DIR).join("index.lock"), "the cache index", LOCK_TIMEOUT)
+}
+
+/// Marks `repository` as used now.
+pub fn record_use(root: &Path, repository: &str) -> Result<(), AppError> {
+    let _lock = lock_index(root)?;
+    let mut index = CacheIndex::load(root);
+    index.repositories.insert(repository.to_string(), IndexEntry { last_used: now() });
+    index.save(root)
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// The writable cache, holding one bare repository per `<owner>/<repo>` under `root`.
+pub struct GitRepositoryCache {
+    root: PathBuf,
+}
+
+impl GitRepositoryCache {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn repository_dir(&self, repository: &str) -> Result<PathBuf, AppError> {
+        match repository.split_once('/') {
+            Some((owner, repo)) if is_plain_name(owner) && is_plain_name(repo) => Ok(self.root.join(owner).join(repo)),
+            _ => Err(AppError::System(format!("Invalid repository '{}': expected owner/repo", repository))),
+        }
+    }
+}
+
+fn is_plain_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\'])
+}
+
+impl RepositoryCache for GitRepositoryCache {
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn list(&self) -> Result<Vec<CachedRepository>, AppError> {
+        let index = CacheIndex::load(&self.root);
+        let mut repos = Vec::new();
+
+        let Ok(owners) = fs::read_dir(&self.root) else { return Ok(repos) };
+        for owner in owners.flatten().filter(|e| e.path().is_dir() && !e.file_name().to_string_lossy().starts_with('.')) {
+            // Dot entries are clones still in progress (or abandoned by a killed process)
+            let entries = fs::read_dir(owner.path()).map_err(AppError::Io)?.flatten()
+                .filter(|e| e.path().is_dir() && !e.file_name().to_string_lossy().starts_with('.'));
+            for repo in entries {
+                let repository = format!("{}/{}", owner.file_name().to_string_lossy(), repo.file_name().to_string_lossy());
+                // Repositories cached before the index existed fall back to their modification time
+                let last_used = index.repositories.get(&repository).map(|e| e.last_used)
+                    .or_else(|| repo.metadata().ok()?.modified().ok()?.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs()))
+                    .unwrap_or(0);
+                repos.push(CachedRepository { repository, size: dir_size(&repo.path()), last_used });
+            }
+        }
+        Ok(repos)
+    }
+
+    fn remove(&self, repository: &str) -> Result<(), AppError> {
+        let dir = self.repository_dir(repository)?;
+        if !dir.exists() {
+            return Err(AppError::System(format!("{} is not in the cache", repository)));
+        }
+        {
+            let _lock = lock_repository(&self.root, repository)?;
+            fs::remove_dir_all(&dir).map_err(AppError::Io)?;
+        }
+        if let Some(owner_dir) = dir.parent() {
+            let _ = fs::remove_dir(owner_dir); // Only succeeds once the owner has no repositories left
+        }
+
+        let _lock = lock_index(&self.root)?;
+        let mut index = CacheIndex::load(&self.root);
+        if index.repositories.remove(repository).is_some() {
+            index.save(&self.root)?;
+        }
+        Ok(())
+    }
+
+    fn verify(&self, repository: &str) -> Result<(), AppError> {
+        let dir = self.repository_dir(repository)?;
+        let _lock = lock_repository(&self.root, repository)?;
+        if !is_usable(&dir) {
+            return Err(AppError::System("clone is incomplete".to_string()));
+        }
+
+        let output = Command::new("git")
+            .current_dir(&dir)
+            .arg("fsck").arg("--no-progress").arg("--no-dangling")
+            .output()
+            .map_err(|e| AppError::System(format!("Failed to execute git fsck: {}", e)))?;
+        if !output.status.success() {
+            let details = String::from_utf8_lossy(&output.stderr);
+            let first = details.lines().next().unwrap_or("git fsck failed");
+            return Err(AppError::System(first.to_string()));
+        }
+        Ok(())
+    }
+
+    fn compact(&self, repository: &str) -> Result<(), AppError> {
+        let dir = self.repository_dir(repository)?;
+        let _lock = lock_repository(&self.root, repository)?;
+        for args in [&["worktree", "prune"][..], &["gc", "--quiet", "--prune=now"][..]] {
+            let output = Command::new("git")
+                .current_dir(&dir)
+                .args(args)
+                .output()
+                .map_err(|e| AppError::System(format!("Failed to execute git {}: {}", args[0], e)))?;
+            if !output.status.success() {
+                return Err(AppError::System(format!("git {} failed for {}: {}", args.join(" "), repository, stderr_tail(&output.stderr))));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else { return 0 };
+    entries.flatten()
+        .map(|entry| match entry.path().symlink_metadata() {
+            Ok(meta) if meta.is_dir() => dir_size(&entry.path()),
+            Ok(meta) => meta.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_locks_are_keyed_by_path() {
+        let root = Path::new("/cache");
+        let a = project_lock_path(root, Path::new("/work/a/app"));
+        assert!(a.starts_with("/cache/.locks/.projects"));
+        assert!(a.file_name().unwrap().to_string_lossy().starts_with("app-"));
+        assert_eq!(a, project_lock_path(root, Path::new("/work/a/app")));
+        assert_ne!(a, project_lock_path(root, Path::new("/work/b/app")));
+    }
+
+    #[test]
+    fn test_read_only_check_leaves_the_cache_untouched() {
+        let tmp = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| assert!(Command::new("git").current_dir(tmp.path()).args(args).output().unwrap().status.success());
+        git(&["init", "-q"]);
+        git(&["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "--allow-empty", "-m", "init"]);
+        let dir = tmp.path().join(".git");
+
+        assert!(is_usable_read_only(&dir));
+        assert!(!dir.join(COMPLETE_MARKER).exists());
+        assert!(is_usable(&dir));
+        assert!(dir.join(COMPLETE_MARKER).exists());
+    }
+}
@@ -1,181 +1,584 @@
-use std::path::Path;
-use std::process::Command;
-use fs_extra::dir::{copy, CopyOptions};
-use crate::core::domain::skill::SkillSource;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use crate::core::domain::skill::{is_commit_hash, SkillSource};
+use crate::core::domain::version::{RefKind, RemoteRef};
+use crate::core::domain::lockfile::LockEntry;
 use crate::core::domain::error::AppError;
+use crate::core::domain::config::DEFAULT_HOST;
+use crate::core::domain::mirror::UrlRules;
 use crate::core::port::skill_fetcher::SkillFetcher;
+use crate::infra::fs::local::{copy_tree, remove_path};
+use crate::infra::git::cache::{is_usable, is_usable_read_only, lock_repository, mark_complete, record_use};
+use crate::infra::git::process::stderr_tail;
+use crate::infra::git::remote::{NetworkPolicy, Remote};
 
-pub struct CliGitFetcher;
+pub struct CliGitFetcher {
+    /// One lock per bare cache: concurrent fetches and checkouts on the same repository conflict.
+    /// Threads of this process queue here; other processes are kept out by the cache's file lock.
+    repo_locks: Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>,
+    /// Serializes updates of the cache index between worker threads.
+    index_lock: Mutex<()>,
+    cache_root: PathBuf,
+    shared_cache: Option<PathBuf>,
+    host: String,
+    tokens: BTreeMap<String, String>,
+    url_rules: UrlRules,
+    network: NetworkPolicy,
+    offline: bool,
+}
 
 impl CliGitFetcher {
-    pub fn new() -> Self {
-        Self
+    /// `cache_root` holds one writable bare repository per `<owner>/<repo>`.
+    pub fn new(cache_root: PathBuf) -> Self {
+        Self {
+            repo_locks: Mutex::new(HashMap::new()),
+            index_lock: Mutex::new(()),
+            cache_root,
+            shared_cache: None,
+            host: DEFAULT_HOST.to_string(),
+            tokens: BTreeMap::new(),
+            url_rules: UrlRules::default(),
+            network: NetworkPolicy::default(),
+            offline: false,
+        }
     }
-}
 
-impl SkillFetcher for CliGitFetcher {
-    fn fetch(&self, source: &SkillSource, dest: &Path, target_commit: Option<&str>) -> Result<String, AppError> {
-        let mut source_str = source.as_str();
-        let mut branch_or_tag = None;
+    /// The git host `owner/repo` sources live on (GitHub by default).
+    pub fn with_host(mut self, host: String) -> Self {
+        self.host = host;
+        self
+    }
+
+    /// Configured access tokens per host. `ARSYNC_TOKEN_<HOST>` and `GITHUB_TOKEN` take precedence.
+    pub fn with_tokens(mut self, tokens: BTreeMap<String, String>) -> Self {
+        self.tokens = tokens;
+        self
+    }
+
+    /// Rewrites and mirrors for the URLs git connects to. Caches stay keyed by `owner/repo`,
+    /// and nothing written to projects mentions the rewritten URLs.
+    pub fn with_url_rules(mut self, url_rules: UrlRules) -> Self {
+        self.url_rules = url_rules;
+        self
+    }
+
+    /// Timeout and retries for clone, fetch and `ls-remote`.
+    pub fn with_network_policy(mut self, network: NetworkPolicy) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// A read-only cache with the same layout, consulted first for locked commits.
+    /// It is never written to; anything it lacks comes from the writable cache.
+    pub fn with_shared_cache(mut self, shared_cache: Option<PathBuf>) -> Self {
+        self.shared_cache = shared_cache;
+        self
+    }
+
+    /// Never touches the network: everything is served from the bare cache,
+    /// and anything the cache does not have is an error.
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
 
-        // Extract branch/tag first from the very end
-        if let Some(idx) = source_str.find('@') {
-            branch_or_tag = Some(&source_str[idx + 1..]);
-            source_str = &source_str[..idx];
-        } else if let Some(idx) = source_str.find('#') {
-            branch_or_tag = Some(&source_str[idx + 1..]);
-            source_str = &source_str[..idx];
+    fn repo_lock(&self, cache_dir: &Path) -> Arc<Mutex<()>> {
+        let mut locks = self.repo_locks.lock().unwrap_or_else(|e| e.into_inner());
+        locks.entry(cache_dir.to_path_buf()).or_default().clone()
+    }
+
+    /// Records the repository as used for `cache list` and `cache gc`. Best effort: the index is only a hint.
+    fn record_use(&self, source: &SkillSource) {
+        let _guard = self.index_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _ = record_use(&self.cache_root, source.repository());
+    }
+
+    fn remote(&self, owner: &str, repo: &str) -> Remote {
+        Remote::new(format!("https://{}/{}/{}.git", self.host, owner, repo), &self.tokens, &self.url_rules, self.network)
+    }
+
+    fn cache_dir_for(&self, owner: &str, repo: &str) -> PathBuf {
+        self.cache_root.join(owner).join(repo)
+    }
+
+    /// The shared cache's copy of `repository` and the full hash of `commit`, if it has that commit.
+    fn shared_cache_with(&self, repository: &str, commit: &str) -> Option<(PathBuf, String)> {
+        let dir = self.shared_cache.as_ref()?.join(repository);
+        if !dir.is_dir() || !is_usable_read_only(&dir) {
+            return None;
         }
+        resolve_commit(&dir, commit).map(|resolved| (dir, resolved))
+    }
+}
 
-        // Now split the path
-        let mut parts = source_str.splitn(3, '/');
-        let owner = parts.next().ok_or_else(|| AppError::System("Invalid source: missing owner".to_string()))?;
-        let repo = parts.next().ok_or_else(|| AppError::System("Invalid source: missing repo".to_string()))?;
-        let subfolder = parts.next().unwrap_or("");
+impl SkillFetcher for CliGitFetcher {
+    fn fetch_batch(&self, items: &[(SkillSource, PathBuf)], target_commit: Option<&str>) -> Result<Vec<Result<LockEntry, AppError>>, AppError> {
+        let Some((first, _)) = items.first() else { return Ok(Vec::new()) };
+        let (owner, repo) = split_repository(first)?;
+        let (_, branch_or_tag) = first.split_ref();
 
-        let url = format!("https://github.com/{}/{}.git", owner, repo);
+        let remote = self.remote(owner, repo);
+
+        // A locked commit found in the read-only shared cache is served from there, without locking or network access.
+        // Should it lack some file contents (it is a partial clone), fall through to the writable cache.
+        if let Some((shared_dir, commit)) = target_commit.and_then(|c| self.shared_cache_with(first.repository(), c)) {
+            if let Ok(results) = extract_skills(&shared_dir, &commit, items, None) {
+                return Ok(results);
+            }
+        }
 
         // 1. Setup Global Cache Path
-        let home_dir = dirs::home_dir().ok_or_else(|| AppError::System("Cannot determine home directory for cache".to_string()))?;
-        let cache_dir = home_dir.join(".arsync").join("cache").join(owner).join(repo);
+        let cache_dir = self.cache_dir_for(owner, repo);
+        let repo_lock = self.repo_lock(&cache_dir);
+        let _repo_guard = repo_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _cache_lock = lock_repository(&self.cache_root, first.repository())?;
 
         // 2. Clone bare repository or fetch updates
-        if !cache_dir.exists() {
-            std::fs::create_dir_all(&cache_dir).map_err(AppError::Io)?;
-            let mut clone_cmd = Command::new("git");
-            clone_cmd.arg("clone").arg("--bare").arg("--filter=blob:none").arg(&url).arg(&cache_dir);
-            let status = clone_cmd.status().map_err(|e| AppError::System(format!("Failed to execute git clone --bare: {}", e)))?;
-            if !status.success() {
-                return Err(AppError::System(format!("Git clone failed for {}", url)));
-            }
-        } else {
-            let mut fetch_cmd = Command::new("git");
-            fetch_cmd.current_dir(&cache_dir).arg("fetch").arg("origin");
-            let status = fetch_cmd.status().map_err(|e| AppError::System(format!("Failed to execute git fetch: {}", e)))?;
-            if !status.success() {
-                // We ignore fetch failures if offline, but ideally log a warning
-                eprintln!("Warning: Failed to fetch updates from {}, using local cache.", url);
-            }
+        ensure_cache(&remote, &cache_dir, target_commit, self.offline)?;
+        self.record_use(first);
+
+        // 3. Resolve the commit to extract, prioritizing target_commit (from lockfile) over branch_or_tag
+        let rev = target_commit.or(branch_or_tag).unwrap_or("HEAD");
+        let resolved_commit = resolve_commit(&cache_dir, rev)
+            .ok_or_else(|| AppError::System(format!("Failed to resolve branch/tag/commit '{}' for {}", rev, remote.url)))?;
+
+        // 4. Extract every skill of the batch straight from the commit's tree
+        extract_skills(&cache_dir, &resolved_commit, items, (!self.offline).then_some(&remote))
+    }
+
+    fn resolve_trees(&self, sources: &[SkillSource], commit: &str) -> Result<Vec<Option<String>>, AppError> {
+        let Some(first) = sources.first() else { return Ok(Vec::new()) };
+        let (owner, repo) = split_repository(first)?;
+        let remote = self.remote(owner, repo);
+        let subpaths: Vec<&str> = sources.iter().map(|s| s.subpath()).collect();
+
+        if let Some((shared_dir, commit)) = self.shared_cache_with(first.repository(), commit) {
+            let trees = existing_trees(&shared_dir, &commit, &subpaths)?;
+            return Ok(subpaths.iter().map(|p| trees.get(p).cloned()).collect());
         }
 
-        // 3. Create a temporary worktree to extract files
-        let tmp_dir = tempfile::tempdir().map_err(|e| AppError::System(format!("Failed to create temp dir: {}", e)))?;
-        let tmp_worktree = tmp_dir.path().to_path_buf();
-        
-        let mut worktree_cmd = Command::new("git");
-        worktree_cmd.current_dir(&cache_dir).arg("worktree").arg("add").arg("-d").arg(&tmp_worktree);
-        
-        // Prioritize target_commit (from lockfile) over branch_or_tag
-        if let Some(commit) = target_commit {
-            worktree_cmd.arg(commit);
-        } else if let Some(b) = branch_or_tag {
-            worktree_cmd.arg(b);
-        } else {
-            // Default branches might differ, so we shouldn't pass anything to let git worktree figure it out,
-            // or we explicitly checkout HEAD
-            worktree_cmd.arg("HEAD");
+        let cache_dir = self.cache_dir_for(owner, repo);
+        let repo_lock = self.repo_lock(&cache_dir);
+        let _repo_guard = repo_lock.lock().unwrap_or_else(|e| e.into_inner());
+        let _cache_lock = lock_repository(&self.cache_root, first.repository())?;
+
+        ensure_cache(&remote, &cache_dir, Some(commit), self.offline)?;
+        self.record_use(first);
+
+        let trees = existing_trees(&cache_dir, commit, &subpaths)?;
+        Ok(subpaths.iter().map(|p| trees.get(p).cloned()).collect())
+    }
+
+    fn resolve_remote_hashes(&self, sources: &[SkillSource]) -> Result<Vec<String>, AppError> {
+        let Some(first) = sources.first() else { return Ok(Vec::new()) };
+        let (owner, repo) = split_repository(first)?;
+        let remote = self.remote(owner, repo);
+
+        if self.offline {
+            return Err(AppError::System(format!("Cannot check {} for updates in offline mode", first.repository())));
         }
 
-        let status = worktree_cmd.status().map_err(|e| AppError::System(format!("Failed to add working tree: {}", e)))?;
-        if !status.success() {
-             return Err(AppError::System(format!("Failed to checkout branch/tag/commit for {}", url)));
+        let wanted: Vec<&str> = sources.iter().map(|s| s.split_ref().1.unwrap_or("HEAD")).collect();
+
+        // A source pinned to a commit can never move, and ls-remote does not advertise raw commits
+        let mut patterns: Vec<&str> = wanted.iter().copied().filter(|r| !is_commit_hash(r)).collect();
+        patterns.sort();
+        patterns.dedup();
+
+        let mut advertised = Vec::new();
+        if !patterns.is_empty() {
+            let output = remote.run("git ls-remote", |endpoint| {
+                let mut cmd = endpoint.command();
+                cmd.arg("ls-remote").arg(&endpoint.url).args(&patterns);
+                cmd
+            })?;
+
+            advertised = String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| line.split_once('\t'))
+                .map(|(hash, name)| (hash.to_string(), name.to_string()))
+                .collect();
         }
 
-        // --- Get the resolved commit hash from the worktree ---
-        let rev_cmd = Command::new("git").current_dir(&tmp_worktree).arg("rev-parse").arg("HEAD").output();
-        let resolved_commit = match rev_cmd {
-            Ok(output) if output.status.success() => {
-                String::from_utf8_lossy(&output.stdout).trim().to_string()
-            },
-            _ => "unknown".to_string() // Fallback, though extremely unlikely to hit if worktree succeeded
-        };
+        wanted.iter()
+            .map(|r| {
+                if is_commit_hash(r) {
+                    return Ok(r.to_string());
+                }
+                match_advertised_ref(&advertised, r)
+                    .ok_or_else(|| AppError::System(format!("Ref '{}' not found in {}", r, remote.url)))
+            })
+            .collect()
+    }
+
+    fn list_refs(&self, source: &SkillSource) -> Result<Vec<RemoteRef>, AppError> {
+        let (owner, repo) = split_repository(source)?;
+        let remote = self.remote(owner, repo);
 
-        // 4. Sparse-checkout if subfolder exists
-        if !subfolder.is_empty() {
-             let mut sparse_cmd = Command::new("git");
-             sparse_cmd.current_dir(&tmp_worktree).arg("sparse-checkout").arg("set").arg(subfolder);
-             let sparse_status = sparse_cmd.status().map_err(|e| AppError::System(format!("Failed to execute git sparse-checkout: {}", e)))?;
-             if !sparse_status.success() {
-                 let _ = Command::new("git").current_dir(&cache_dir).arg("worktree").arg("remove").arg("-f").arg(&tmp_worktree).status();
-                 return Err(AppError::System(format!("Git sparse-checkout failed for {}", subfolder)));
-             }
+        if self.offline {
+            return Err(AppError::System(format!("Cannot list the versions of {} in offline mode", source.repository())));
         }
 
-        // 5. Move the fetched folder to the destination
-        let src_path = if subfolder.is_empty() {
-            tmp_worktree.clone()
-        } else {
-            tmp_worktree.join(subfolder)
-        };
+        let output = remote.run("git ls-remote", |endpoint| {
+            let mut cmd = endpoint.command();
+            cmd.arg("ls-remote").arg("--tags").arg("--heads").arg(&endpoint.url);
+            cmd
+        })?;
+
+        let mut refs = parse_ls_remote(&String::from_utf8_lossy(&output.stdout));
 
-        if !src_path.exists() {
-            let _ = Command::new("git").current_dir(&cache_dir).arg("worktree").arg("remove").arg("-f").arg(&tmp_worktree).status();
-            return Err(AppError::System(format!("Source path '{}' not found in repository", subfolder)));
+        // Dates are not advertised by ls-remote; fill them in from the bare cache when we have one
+        let cache_dir = self.cache_dir_for(owner, repo);
+        if cache_dir.exists() {
+            annotate_dates(&cache_dir, &mut refs);
         }
 
-        if dest.exists() {
-            std::fs::remove_dir_all(dest).map_err(AppError::Io)?;
+        Ok(refs)
+    }
+}
+
+fn split_repository(source: &SkillSource) -> Result<(&str, &str), AppError> {
+    match source.repository().split_once('/') {
+        Some((owner, repo)) if !owner.is_empty() && !repo.is_empty() => Ok((owner, repo)),
+        _ => Err(AppError::System(format!("Invalid source: expected owner/repo in '{}'", source.as_str()))),
+    }
+}
+
+/// Makes sure the bare cache exists and is current enough to serve `needed_commit`.
+/// A commit already in the cache is immutable, so no network access is needed for it.
+/// Offline, the cache is used as-is and a missing repository or commit is an error.
+fn ensure_cache(remote: &Remote, cache_dir: &Path, needed_commit: Option<&str>, offline: bool) -> Result<(), AppError> {
+    let url = &remote.url;
+    let present = cache_dir.exists();
+    let usable = present && is_usable(cache_dir);
+
+    if offline {
+        if !present {
+            return Err(AppError::System(format!("{} is not in the cache (offline mode); run `arsync fetch` while online first", url)));
         }
-        if let Some(parent) = dest.parent() {
-            std::fs::create_dir_all(parent).map_err(AppError::Io)?;
+        if !usable {
+            return Err(AppError::System(format!("The cache of {} is corrupt and cannot be repaired offline", url)));
         }
+        if let Some(commit) = needed_commit {
+            if resolve_commit(cache_dir, commit).is_none() {
+                return Err(AppError::System(format!("Commit {} of {} is not in the cache (offline mode); run `arsync fetch` while online first", commit, url)));
+            }
+        }
+        return Ok(());
+    }
 
-        let mut options = CopyOptions::new();
-        options.copy_inside = true;
-        
-        let result = copy(&src_path, dest, &options);
+    if present && !usable {
+        eprintln!("Warning: The cache of {} is incomplete or corrupt, cloning it again.", url);
+        std::fs::remove_dir_all(cache_dir).map_err(AppError::Io)?;
+    }
+    if !usable {
+        return clone_cache(remote, cache_dir);
+    }
 
-        let _ = Command::new("git").current_dir(&cache_dir).arg("worktree").arg("remove").arg("-f").arg(&tmp_worktree).status();
+    if let Some(commit) = needed_commit {
+        if is_commit_hash(commit) && resolve_commit(cache_dir, commit).is_some() {
+            return Ok(());
+        }
+    }
 
-        result.map_err(|e| AppError::System(format!("Failed to copy directory: {}", e)))?;
+    // Bare clones carry no fetch refspec, so spell it out to keep local branches and tags current.
+    // The URL is passed in too, in case the configured host or mirror changed since the clone.
+    let fetched = remote.run("git fetch", |endpoint| {
+        let mut cmd = endpoint.command();
+        cmd.current_dir(cache_dir)
+            .arg("-c").arg(format!("remote.origin.url={}", endpoint.url))
+            .arg("fetch").arg("origin")
+            .arg("+refs/heads/*:refs/heads/*")
+            .arg("+refs/tags/*:refs/tags/*");
+        cmd
+    });
+    match fetched {
+        // Losing access is not something the stale cache should paper over
+        Err(e @ AppError::Auth(_)) => Err(e),
+        Err(_) => {
+            eprintln!("Warning: Failed to fetch updates from {}, using local cache.", url);
+            Ok(())
+        }
+        Ok(_) => Ok(()),
+    }
+}
+
+/// Clones into a hidden directory next to `cache_dir` and moves it into place only once complete,
+/// so an interrupted clone never leaves a half-populated cache behind.
+fn clone_cache(remote: &Remote, cache_dir: &Path) -> Result<(), AppError> {
+    let parent = cache_dir.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent).map_err(AppError::Io)?;
+    let partial = tempfile::Builder::new().prefix(".arsync-clone-").tempdir_in(parent)
+        .map_err(|e| AppError::System(format!("Failed to create temp dir: {}", e)))?;
+    let cloned = partial.path().join("bare");
+
+    remote.run("git clone", |endpoint| {
+        // Start each attempt from scratch
+        let _ = std::fs::remove_dir_all(&cloned);
+        let mut cmd = endpoint.command();
+        cmd.arg("clone").arg("--bare").arg("--filter=blob:none").arg(&endpoint.url).arg(&cloned);
+        cmd
+    })?;
 
-        if subfolder.is_empty() {
-           let dest_git = dest.join(".git");
-           if dest_git.exists() {
-               let _ = if dest_git.is_dir() { std::fs::remove_dir_all(&dest_git) } else { std::fs::remove_file(&dest_git) };
-           }
+    mark_complete(&cloned).map_err(AppError::Io)?;
+    std::fs::rename(&cloned, cache_dir).map_err(AppError::Io)
+}
+
+fn resolve_commit(cache_dir: &Path, rev: &str) -> Option<String> {
+    let output = Command::new("git")
+        .current_dir(cache_dir)
+        .arg("rev-parse").arg("--verify").arg("--quiet")
+        .arg(format!("{}^{{commit}}", rev))
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Returns the tree hash of each of `subpaths` that exists as a directory in `commit`, with a single `cat-file` call.
+fn existing_trees<'s>(cache_dir: &Path, commit: &str, subpaths: &[&'s str]) -> Result<HashMap<&'s str, String>, AppError> {
+    let mut child = Command::new("git")
+        .current_dir(cache_dir)
+        .arg("cat-file").arg("--batch-check")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::System(format!("Failed to execute git cat-file: {}", e)))?;
+
+    let input: String = subpaths.iter().map(|p| format!("{}:{}\n", commit, p)).collect();
+    child.stdin.take().expect("stdin is piped").write_all(input.as_bytes()).map_err(AppError::Io)?;
+    let output = child.wait_with_output().map_err(AppError::Io)?;
+
+    // One output line per input line, in order: "<oid> <type> <size>" or "<object> missing"
+    Ok(subpaths.iter()
+        .zip(String::from_utf8_lossy(&output.stdout).lines())
+        .filter_map(|(p, line)| {
+            let mut cols = line.split_whitespace();
+            match (cols.next(), cols.next()) {
+                (Some(oid), Some("tree")) => Some((*p, oid.to_string())),
+                _ => None,
+            }
+        })
+        .collect())
+}
+
+/// Materializes each skill's subfolder of `commit` into its destination.
+///
+/// Files are checked out from the bare cache through a throwaway index into a staging
+/// directory next to the destinations (so the final move is a rename), which keeps
+/// executable bits and symlinks exactly as git records them and leaves no worktree
+/// metadata behind in the cache if the process is killed.
+///
+/// File contents missing from the partial clone are fetched lazily from `remote`; without one (offline
+/// or from the shared cache) they are an error.
+fn extract_skills(cache_dir: &Path, commit: &str, items: &[(SkillSource, PathBuf)], remote: Option<&Remote>) -> Result<Vec<Result<LockEntry, AppError>>, AppError> {
+    let mut subpaths: Vec<&str> = items.iter().map(|(source, _)| source.subpath()).collect();
+    subpaths.sort();
+    subpaths.dedup();
+    let present = existing_trees(cache_dir, commit, &subpaths)?;
+
+    let staging_parent = items[0].1.parent().unwrap_or(Path::new("."));
+    std::fs::create_dir_all(staging_parent).map_err(AppError::Io)?;
+    let staging = tempfile::Builder::new().prefix(".arsync-staging-").tempdir_in(staging_parent)
+        .map_err(|e| AppError::System(format!("Failed to create temp dir: {}", e)))?;
+    let tree_root = staging.path().join("tree");
+    std::fs::create_dir_all(&tree_root).map_err(AppError::Io)?;
+
+    let mut pathspecs: Vec<&str> = present.keys().map(|p| if p.is_empty() { "." } else { *p }).collect();
+    pathspecs.sort();
+    if !pathspecs.is_empty() {
+        let index_dir = tempfile::tempdir().map_err(|e| AppError::System(format!("Failed to create temp dir: {}", e)))?;
+        let checkout = |mut cmd: Command| {
+            cmd.current_dir(cache_dir)
+                .env("GIT_INDEX_FILE", index_dir.path().join("index"))
+                .arg("--work-tree").arg(&tree_root)
+                .arg("checkout").arg(commit).arg("--")
+                .args(&pathspecs);
+            cmd
+        };
+        match remote {
+            Some(remote) => {
+                remote.run("git checkout", |endpoint| {
+                    let mut cmd = endpoint.command();
+                    cmd.arg("-c").arg(format!("remote.origin.url={}", endpoint.url));
+                    checkout(cmd)
+                })?;
+            }
+            None => {
+                // The cache is a partial clone: forbid every transport so missing file contents
+                // are reported instead of being fetched lazily
+                let mut cmd = Command::new("git");
+                cmd.env("GIT_ALLOW_PROTOCOL", "");
+                let output = checkout(cmd).output()
+                    .map_err(|e| AppError::System(format!("Failed to execute git checkout: {}", e)))?;
+                if !output.status.success() {
+                    return Err(AppError::System(format!(
+                        "Failed to extract {} at {} (some files are not in the cache, offline mode): {}",
+                        pathspecs.join(", "), commit, stderr_tail(&output.stderr)
+                    )));
+                }
+            }
         }
+    }
+
+    // 5. Move each extracted folder to its destination (copy when several skills share one folder)
+    Ok(items.iter()
+        .map(|(source, dest)| {
+            let subfolder = source.subpath();
+            let Some(tree) = present.get(subfolder) else {
+                return Err(AppError::System(format!("Source path '{}' not found in repository", subfolder)));
+            };
 
-        Ok(resolved_commit)
+            let src_path = if subfolder.is_empty() { tree_root.clone() } else { tree_root.join(subfolder) };
+            // Renaming would take files away from another skill reading the same or an enclosing folder
+            let shared = items.iter().filter(|(s, _)| overlaps(s.subpath(), subfolder)).count() > 1;
+
+            remove_path(dest).map_err(AppError::Io)?;
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent).map_err(AppError::Io)?;
+            }
+
+            if shared || std::fs::rename(&src_path, dest).is_err() {
+                copy_tree(&src_path, dest).map_err(|e| AppError::System(format!("Failed to copy directory: {}", e)))?;
+            }
+            Ok(LockEntry::new(commit.to_string(), tree.clone()))
+        })
+        .collect())
+}
+
+/// True when one repository folder is the same as, or nested inside, the other.
+fn overlaps(a: &str, b: &str) -> bool {
+    let within = |inner: &str, outer: &str| {
+        outer.is_empty() || inner == outer || inner.strip_prefix(outer).is_some_and(|rest| rest.starts_with('/'))
+    };
+    within(a, b) || within(b, a)
+}
+
+/// Picks the commit for `wanted` out of `ls-remote <url> <patterns>` output,
+/// preferring branches over tags and peeled tags over tag objects.
+fn match_advertised_ref(advertised: &[(String, String)], wanted: &str) -> Option<String> {
+    let candidates = if wanted == "HEAD" {
+        vec!["HEAD".to_string()]
+    } else {
+        vec![
+            format!("refs/heads/{}", wanted),
+            format!("refs/tags/{}^{{}}", wanted),
+            format!("refs/tags/{}", wanted),
+            wanted.to_string(),
+        ]
+    };
+
+    candidates.iter()
+        .find_map(|c| advertised.iter().find(|(_, name)| name == c))
+        .map(|(hash, _)| hash.clone())
+}
+
+/// Looks up committer dates for every ref whose commit is present in the cache.
+/// Commits the cache has not seen yet are silently left without a date.
+fn annotate_dates(cache_dir: &Path, refs: &mut [RemoteRef]) {
+    let mut cmd = Command::new("git");
+    cmd.current_dir(cache_dir)
+        .arg("log").arg("--no-walk=unsorted").arg("--ignore-missing").arg("--format=%H %cs");
+    for r in refs.iter() {
+        cmd.arg(&r.commit);
     }
 
-    fn resolve_remote_hash(&self, source: &SkillSource) -> Result<String, AppError> {
-        let mut source_str = source.as_str();
-        let mut branch_or_tag: Option<&str> = None;
+    let Ok(output) = cmd.output() else { return };
+    if !output.status.success() {
+        return;
+    }
 
-        if let Some(idx) = source_str.find('@') {
-            branch_or_tag = Some(&source_str[idx + 1..]);
-            source_str = &source_str[..idx];
-        } else if let Some(idx) = source_str.find('#') {
-            branch_or_tag = Some(&source_str[idx + 1..]);
-            source_str = &source_str[..idx];
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for line in stdout.lines() {
+        if let Some((hash, date)) = line.split_once(' ') {
+            for r in refs.iter_mut().filter(|r| r.commit == hash) {
+                r.date = Some(date.to_string());
+            }
         }
+    }
+}
 
-        let mut parts = source_str.splitn(3, '/');
-        let owner = parts.next().ok_or_else(|| AppError::System("Invalid source: missing owner".to_string()))?;
-        let repo = parts.next().ok_or_else(|| AppError::System("Invalid source: missing repo".to_string()))?;
+/// Parses `git ls-remote --tags --heads` output. Annotated tags are advertised twice
+/// (the tag object and a `^{}` line with the commit); the peeled commit wins.
+fn parse_ls_remote(stdout: &str) -> Vec<RemoteRef> {
+    let mut refs: Vec<RemoteRef> = Vec::new();
 
-        let url = format!("https://github.com/{}/{}.git", owner, repo);
-        let ref_name = branch_or_tag.unwrap_or("HEAD");
+    for line in stdout.lines() {
+        let mut cols = line.split_whitespace();
+        let (Some(hash), Some(full_ref)) = (cols.next(), cols.next()) else { continue };
 
-        let output = Command::new("git")
-            .arg("ls-remote")
-            .arg(&url)
-            .arg(ref_name)
-            .output()
-            .map_err(|e| AppError::System(format!("Failed to execute git ls-remote: {}", e)))?;
+        let (kind, name) = if let Some(name) = full_ref.strip_prefix("refs/heads/") {
+            (RefKind::Branch, name)
+        } else if let Some(name) = full_ref.strip_prefix("refs/tags/") {
+            (RefKind::Tag, name)
+        } else {
+            continue;
+        };
+
+        let (name, peeled) = match name.strip_suffix("^{}") {
+            Some(n) => (n, true),
+            None => (name, false),
+        };
 
-        if !output.status.success() {
-            return Err(AppError::System(format!("git ls-remote failed for {}", url)));
+        if let Some(existing) = refs.iter_mut().find(|r| r.kind == kind && r.name == name) {
+            if peeled {
+                existing.commit = hash.to_string();
+            }
+        } else {
+            refs.push(RemoteRef { name: name.to_string(), kind, commit: hash.to_string(), date: None });
         }
+    }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let hash = stdout.lines()
-            .next()
-            .and_then(|line| line.split_whitespace().next())
-            .unwrap_or("unknown")
-            .to_string();
+    refs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ls_remote_peels_annotated_tags() {
+        let out = "\
+aaa\trefs/heads/main
+bbb\trefs/tags/v1.0.0
+ccc\trefs/tags/v1.0.0^{}
+ddd\trefs/tags/v1.1.0
+";
+        let refs = parse_ls_remote(out);
+        assert_eq!(refs.len(), 3);
+        assert_eq!(refs[0], RemoteRef { name: "main".to_string(), kind: RefKind::Branch, commit: "aaa".to_string(), date: None });
+        assert_eq!(refs[1].commit, "ccc");
+        assert_eq!(refs[2].commit, "ddd");
+    }
+
+    #[test]
+    fn test_match_advertised_ref() {
+        let advertised: Vec<(String, String)> = [
+            ("h1", "HEAD"),
+            ("b1", "refs/heads/main"),
+            ("t1", "refs/tags/v1.0.0"),
+            ("c1", "refs/tags/v1.0.0^{}"),
+            ("t2", "refs/tags/main"),
+        ].iter().map(|(h, n)| (h.to_string(), n.to_string())).collect();
+
+        assert_eq!(match_advertised_ref(&advertised, "HEAD").as_deref(), Some("h1"));
+        assert_eq!(match_advertised_ref(&advertised, "main").as_deref(), Some("b1"));
+        assert_eq!(match_advertised_ref(&advertised, "v1.0.0").as_deref(), Some("c1"));
+        assert_eq!(match_advertised_ref(&advertised, "refs/tags/v1.0.0").as_deref(), Some("t1"));
+        assert_eq!(match_advertised_ref(&advertised, "missing"), None);
+    }
 
-        Ok(hash)
+    #[test]
+    fn test_overlaps() {
+        assert!(overlaps("skills/a", "skills/a"));
+        assert!(overlaps("skills/a/nested", "skills/a"));
+        assert!(overlaps("", "skills/a"));
+        assert!(!overlaps("skills/a", "skills/ab"));
+        assert!(!overlaps("skills/a", "skills/b"));
     }
 }
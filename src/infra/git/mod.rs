@@ -1 +1,4 @@
 pub mod fetcher;
+pub mod cache;
+pub mod remote;
+pub mod process;
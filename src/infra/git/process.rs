@@ -0,0 +1,98 @@
+use std::io::{self, Read};
+use std::process::{Child, Command, Output, Stdio};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Runs `cmd` with stdout and stderr captured, killing it once `timeout` has passed.
+/// Returns `None` when it timed out.
+pub fn output_with_timeout(cmd: &mut Command, timeout: Option<Duration>) -> io::Result<Option<Output>> {
+    let Some(timeout) = timeout else { return cmd.output().map(Some) };
+
+    cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    // Its own process group, so a timeout also kills the helpers git spawns (e.g. git-remote-https)
+    #[cfg(unix)]
+    std::os::unix::process::CommandExt::process_group(cmd, 0);
+    let mut child = cmd.spawn()?;
+    // Drain both pipes while waiting, so a chatty process cannot block on a full pipe
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(Output {
+                status,
+                stdout: stdout.join().unwrap_or_default(),
+                stderr: stderr.join().unwrap_or_default(),
+            }));
+        }
+        if Instant::now() >= deadline {
+            kill(&mut child);
+            let _ = child.wait();
+            // With the whole group gone nothing holds the pipes open, so the readers finish
+            let _ = stdout.join();
+            let _ = stderr.join();
+            return Ok(None);
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(unix)]
+fn kill(child: &mut Child) {
+    // SAFETY: kill(2) has no memory-safety preconditions; a negative pid names the process group
+    unsafe { libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL) };
+}
+
+#[cfg(not(unix))]
+fn kill(child: &mut Child) {
+    let _ = child.kill();
+}
+
+fn drain<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    })
+}
+
+/// The last few meaningful lines of a git command's stderr, for error messages.
+/// Progress output is redrawn with carriage returns, so those count as line breaks too.
+pub fn stderr_tail(stderr: &[u8]) -> String {
+    let stderr = String::from_utf8_lossy(stderr);
+    let lines: Vec<&str> = stderr.split(['\n', '\r']).map(str::trim).filter(|l| !l.is_empty()).collect();
+    lines[lines.len().saturating_sub(3)..].join(" / ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stderr_tail() {
+        let stderr = b"Cloning into bare repository 'x'...\nremote: Counting objects: 50% (1/2)\rremote: Counting objects: 100% (2/2)\nerror: RPC failed; curl 56\nfatal: early EOF\n";
+        assert_eq!(stderr_tail(stderr), "remote: Counting objects: 100% (2/2) / error: RPC failed; curl 56 / fatal: early EOF");
+        assert_eq!(stderr_tail(b""), "");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_timeout_kills_the_process() {
+        let started = Instant::now();
+        let output = output_with_timeout(Command::new("sleep").arg("5"), Some(Duration::from_millis(100))).unwrap();
+        assert!(output.is_none());
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        // A background process still holding the pipes is killed along with its parent
+        let started = Instant::now();
+        let output = output_with_timeout(Command::new("sh").args(["-c", "sleep 5 & sleep 5"]), Some(Duration::from_millis(100))).unwrap();
+        assert!(output.is_none());
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let output = output_with_timeout(Command::new("echo").arg("hi"), Some(Duration::from_secs(5))).unwrap().unwrap();
+        assert_eq!(output.stdout, b"hi\n");
+    }
+}
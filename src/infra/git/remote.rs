@@ -0,0 +1,237 @@
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::env;
+use std::process::{Command, Output};
+use std::thread;
+use std::time::Duration;
+use crate::core::domain::config::{token_env_var, token_for, DEFAULT_HOST};
+use crate::core::domain::error::AppError;
+use crate::core::domain::mirror::{host_of, UrlRules};
+use crate::infra::git::process::{output_with_timeout, stderr_tail};
+
+/// Set on git processes that run arsync as their askpass helper; holds the token to answer with.
+const ASKPASS_TOKEN_VAR: &str = "ARSYNC_ASKPASS_TOKEN";
+
+/// How long a network command may run and how often transient failures are retried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkPolicy {
+    /// `None` waits forever
+    pub timeout: Option<Duration>,
+    pub retries: u32,
+}
+
+impl NetworkPolicy {
+    pub const DEFAULT_TIMEOUT_SECS: u64 = 600;
+    pub const DEFAULT_RETRIES: u32 = 2;
+}
+
+impl Default for NetworkPolicy {
+    fn default() -> Self {
+        Self { timeout: Some(Duration::from_secs(Self::DEFAULT_TIMEOUT_SECS)), retries: Self::DEFAULT_RETRIES }
+    }
+}
+
+/// A repository under its canonical URL, and the places it can actually be fetched from:
+/// the (possibly rewritten) primary URL first, then the host's mirrors.
+pub struct Remote {
+    /// The URL projects refer to; used in messages, never necessarily connected to
+    pub url: String,
+    endpoints: Vec<Endpoint>,
+    /// The endpoint that last worked, tried first next time
+    preferred: Cell<usize>,
+    policy: NetworkPolicy,
+}
+
+/// One URL to fetch from, with the access token for its host.
+pub struct Endpoint {
+    pub url: String,
+    host: String,
+    token: Option<String>,
+}
+
+impl Remote {
+    pub fn new(url: String, tokens: &BTreeMap<String, String>, rules: &UrlRules, policy: NetworkPolicy) -> Self {
+        let endpoints = rules.candidates(&url).into_iter().map(|url| Endpoint::new(url, tokens)).collect();
+        Self { url, endpoints, preferred: Cell::new(0), policy }
+    }
+
+    /// Runs the network command built by `command` against each endpoint in turn until one succeeds.
+    /// Transient failures (timeouts, dropped connections, server errors) are retried on the same
+    /// endpoint with exponential backoff first. When all endpoints fail, the first failure is reported.
+    pub fn run(&self, what: &str, command: impl Fn(&Endpoint) -> Command) -> Result<Output, AppError> {
+        let preferred = self.preferred.get();
+        let order: Vec<usize> = std::iter::once(preferred).chain((0..self.endpoints.len()).filter(|&i| i != preferred)).collect();
+
+        let mut first_error = None;
+        for (attempt, &i) in order.iter().enumerate() {
+            let endpoint = &self.endpoints[i];
+            match self.run_with_retries(what, endpoint, &command)? {
+                Ok(output) => {
+                    self.preferred.set(i);
+                    return Ok(output);
+                }
+                Err(error) => {
+                    if let Some(&next) = order.get(attempt + 1) {
+                        eprintln!("Warning: {} failed for {}, trying {}", what, endpoint.url, self.endpoints[next].url);
+                    }
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        Err(first_error.expect("a remote has at least one endpoint"))
+    }
+
+    /// The outer error means git could not be started at all; the inner one that the command failed.
+    fn run_with_retries(&self, what: &str, endpoint: &Endpoint, command: &impl Fn(&Endpoint) -> Command) -> Result<Result<Output, AppError>, AppError> {
+        let mut attempt = 0;
+        loop {
+            let output = output_with_timeout(&mut command(endpoint), self.policy.timeout)
+                .map_err(|e| AppError::System(format!("Failed to execute {}: {}", what, e)))?;
+            let (error, transient) = match output {
+                Some(output) if output.status.success() => return Ok(Ok(output)),
+                Some(output) => (endpoint.failure(what, &output), is_transient_failure(&String::from_utf8_lossy(&output.stderr))),
+                None => {
+                    let secs = self.policy.timeout.map_or(0, |t| t.as_secs());
+                    (AppError::System(format!("{} timed out after {}s for {}", what, secs, endpoint.url)), true)
+                }
+            };
+            if !transient || attempt >= self.policy.retries {
+                return Ok(Err(error));
+            }
+            let delay = Duration::from_secs(1 << attempt.min(5));
+            eprintln!("Warning: {} failed for {}, retrying in {}s", what, endpoint.url, delay.as_secs());
+            thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+}
+
+impl Endpoint {
+    fn new(url: String, tokens: &BTreeMap<String, String>) -> Self {
+        let host = host_of(&url).to_string();
+        let token = token_for(&host, |name| env::var(name).ok(), tokens);
+        Self { url, host, token }
+    }
+
+    /// A git command that never prompts on the terminal. The token is answered through the askpass
+    /// helper (arsync itself), so it never appears in the URL, on the command line or in the cache's config.
+    /// Credential helpers configured in git are still consulted first.
+    pub fn command(&self) -> Command {
+        let mut cmd = Command::new("git");
+        cmd.env("GIT_TERMINAL_PROMPT", "0");
+        if let (Some(token), Ok(exe)) = (&self.token, env::current_exe()) {
+            cmd.env("GIT_ASKPASS", exe).env(ASKPASS_TOKEN_VAR, token);
+        }
+        cmd
+    }
+
+    /// Turns a failed network command into an error, recognizing authentication failures.
+    fn failure(&self, what: &str, output: &Output) -> AppError {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_auth_failure(&stderr) {
+            return AppError::Auth(self.auth_hint());
+        }
+        AppError::System(format!("{} failed for {}: {}", what, self.url, stderr_tail(&output.stderr)))
+    }
+
+    fn auth_hint(&self) -> String {
+        if self.token.is_some() {
+            return format!("{} rejected the token for {}; check that it is valid and can read the repository", self.host, self.url);
+        }
+        let github = if self.host == DEFAULT_HOST { " (or GITHUB_TOKEN)" } else { "" };
+        format!(
+            "{} needs credentials for {}. Set {}{}, run `arsync config set token.{} <token>`, or configure a git credential helper",
+            self.host, self.url, token_env_var(&self.host), github, self.host
+        )
+    }
+}
+
+/// Git's messages when credentials are missing or refused. Hosts answer "not found" for
+/// private repositories the caller cannot see, so that counts as well.
+fn is_auth_failure(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    [
+        "authentication failed",
+        "could not read username",
+        "could not read password",
+        "terminal prompts disabled",
+        "repository not found",
+        "the requested url returned error: 401",
+        "the requested url returned error: 403",
+        "permission denied (publickey",
+    ].iter().any(|pattern| stderr.contains(pattern))
+}
+
+/// Failures worth retrying: the network or the server hiccuped, the request itself was fine.
+fn is_transient_failure(stderr: &str) -> bool {
+    let stderr = stderr.to_lowercase();
+    [
+        "could not resolve host",
+        "connection timed out",
+        "operation timed out",
+        "connection reset",
+        "early eof",
+        "the remote end hung up unexpectedly",
+        "rpc failed",
+        "the requested url returned error: 5",
+        "unexpected disconnect",
+        "gnutls_handshake",
+        "ssl_read",
+    ].iter().any(|pattern| stderr.contains(pattern))
+}
+
+/// When git runs arsync as `GIT_ASKPASS`, prints the answer to its prompt and returns `true`.
+/// Anything that is not one of git's credential prompts is left to the normal command line.
+pub fn answer_askpass() -> bool {
+    let Ok(token) = env::var(ASKPASS_TOKEN_VAR) else { return false };
+    let args: Vec<String> = env::args().skip(1).collect();
+    let [prompt] = args.as_slice() else { return false };
+    match askpass_answer(prompt, &token) {
+        Some(answer) => {
+            println!("{}", answer);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The answer to git's `Username for '<url>': ` or `Password for '<url>': ` prompt.
+/// Any username works with tokens on the common hosts; the password prompt gets the token.
+fn askpass_answer<'t>(prompt: &str, token: &'t str) -> Option<&'t str> {
+    if prompt.starts_with("Username for '") {
+        Some("x-access-token")
+    } else if prompt.starts_with("Password for '") {
+        Some(token)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_askpass_answers_only_credential_prompts() {
+        assert_eq!(askpass_answer("Username for 'https://github.com': ", "secret"), Some("x-access-token"));
+        assert_eq!(askpass_answer("Password for 'https://x-access-token@github.com': ", "secret"), Some("secret"));
+        assert_eq!(askpass_answer("list", "secret"), None);
+        assert_eq!(askpass_answer("--help", "secret"), None);
+    }
+
+    #[test]
+    fn test_is_auth_failure() {
+        assert!(is_auth_failure("fatal: could not read Username for 'https://github.com': terminal prompts disabled"));
+        assert!(is_auth_failure("remote: Repository not found.\nfatal: repository 'https://github.com/o/r.git/' not found"));
+        assert!(is_auth_failure("fatal: Authentication failed for 'https://git.example.com/o/r.git/'"));
+        assert!(!is_auth_failure("fatal: unable to access 'https://github.com/o/r.git/': Could not resolve host: github.com"));
+    }
+
+    #[test]
+    fn test_is_transient_failure() {
+        assert!(is_transient_failure("error: RPC failed; HTTP 502 curl 22 The requested URL returned error: 502\nfatal: expected flush after ref listing"));
+        assert!(is_transient_failure("fatal: unable to access 'https://github.com/o/r.git/': Could not resolve host: github.com"));
+        assert!(!is_transient_failure("fatal: Authentication failed for 'https://github.com/o/r.git/'"));
+        assert!(!is_transient_failure("fatal: couldn't find remote ref refs/heads/nope"));
+    }
+}
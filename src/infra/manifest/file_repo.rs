@@ -12,6 +12,12 @@ impl FileManifestRepository {
     }
 }
 
+impl Default for FileManifestRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ManifestRepository for FileManifestRepository {
     fn load(&self, path: &Path) -> Result<Manifest, AppError> {
         let content = fs::read_to_string(path).map_err(AppError::Io)?;
@@ -12,6 +12,12 @@ impl FileLockfileRepository {
     }
 }
 
+impl Default for FileLockfileRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LockfileRepository for FileLockfileRepository {
     fn load(&self, path: &Path) -> Result<Lockfile, AppError> {
         if !path.exists() {
@@ -1,3 +1,8 @@
+pub mod config;
 pub mod fs;
 pub mod git;
 pub mod manifest;
+pub mod store;
+pub mod prompt;
+pub mod target;
+pub mod paths;
@@ -0,0 +1,106 @@
+use std::env;
+use std::path::{Path, PathBuf};
+use crate::core::domain::error::AppError;
+
+/// Where arsync keeps its machine-wide data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArsyncPaths {
+    /// Writable cache of bare repositories
+    pub cache: PathBuf,
+    /// Content-addressed skill store used by the `symlink` and `hardlink` install modes
+    pub store: PathBuf,
+    /// Read-only cache (e.g. baked into a container image) consulted before `cache`
+    pub shared_cache: Option<PathBuf>,
+    /// User configuration file
+    pub config: PathBuf,
+}
+
+impl ArsyncPaths {
+    /// Resolves the locations from the environment:
+    ///
+    /// 1. `ARSYNC_CACHE_DIR` for the cache, `ARSYNC_HOME` for everything (`<home>/cache`, `<home>/store`)
+    /// 2. `~/.arsync` when it already exists, so existing caches keep being used
+    /// 3. the XDG base directories on Linux (`$XDG_CACHE_HOME/arsync`, `$XDG_DATA_HOME/arsync/store`)
+    /// 4. `~/.arsync` elsewhere
+    ///
+    /// `ARSYNC_SHARED_CACHE` points at an optional read-only cache layer. The user configuration is
+    /// `<ARSYNC_HOME>/config.toml`, or `$XDG_CONFIG_HOME/arsync/config.toml` (`~/.config/arsync/config.toml`).
+    pub fn from_env() -> Result<Self, AppError> {
+        let home = dirs::home_dir();
+        let legacy_exists = home.as_ref().is_some_and(|h| h.join(".arsync").is_dir());
+        resolve(&|name| env::var_os(name).filter(|v| !v.is_empty()).map(PathBuf::from), home.as_deref(), legacy_exists, cfg!(target_os = "linux"))
+    }
+}
+
+fn resolve(var: &dyn Fn(&str) -> Option<PathBuf>, home: Option<&Path>, legacy_exists: bool, linux: bool) -> Result<ArsyncPaths, AppError> {
+    let shared_cache = var("ARSYNC_SHARED_CACHE");
+
+    if let Some(arsync_home) = var("ARSYNC_HOME") {
+        return Ok(ArsyncPaths {
+            cache: var("ARSYNC_CACHE_DIR").unwrap_or_else(|| arsync_home.join("cache")),
+            store: arsync_home.join("store"),
+            shared_cache,
+            config: arsync_home.join("config.toml"),
+        });
+    }
+
+    let home = home.ok_or_else(|| AppError::System("Cannot determine home directory; set ARSYNC_HOME".to_string()))?;
+    let legacy = home.join(".arsync");
+    // The XDG spec says relative paths in these variables are invalid and must be ignored
+    let xdg = |name: &str, fallback: &str| var(name).filter(|p| p.is_absolute()).unwrap_or_else(|| home.join(fallback));
+
+    let (cache, store) = if legacy_exists || !linux {
+        (legacy.join("cache"), legacy.join("store"))
+    } else {
+        (xdg("XDG_CACHE_HOME", ".cache").join("arsync"), xdg("XDG_DATA_HOME", ".local/share").join("arsync").join("store"))
+    };
+
+    Ok(ArsyncPaths {
+        cache: var("ARSYNC_CACHE_DIR").unwrap_or(cache),
+        store,
+        shared_cache,
+        config: xdg("XDG_CONFIG_HOME", ".config").join("arsync").join("config.toml"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve_with(vars: &[(&str, &str)], legacy_exists: bool, linux: bool) -> ArsyncPaths {
+        let vars: HashMap<String, PathBuf> = vars.iter().map(|(k, v)| (k.to_string(), PathBuf::from(v))).collect();
+        resolve(&|name| vars.get(name).cloned(), Some(Path::new("/home/u")), legacy_exists, linux).unwrap()
+    }
+
+    #[test]
+    fn test_xdg_defaults_on_linux() {
+        let paths = resolve_with(&[], false, true);
+        assert_eq!(paths.cache, PathBuf::from("/home/u/.cache/arsync"));
+        assert_eq!(paths.store, PathBuf::from("/home/u/.local/share/arsync/store"));
+        assert_eq!(paths.config, PathBuf::from("/home/u/.config/arsync/config.toml"));
+
+        let paths = resolve_with(&[("XDG_CACHE_HOME", "/xdg/cache"), ("XDG_DATA_HOME", "relative")], false, true);
+        assert_eq!(paths.cache, PathBuf::from("/xdg/cache/arsync"));
+        assert_eq!(paths.store, PathBuf::from("/home/u/.local/share/arsync/store"));
+    }
+
+    #[test]
+    fn test_existing_home_dir_and_other_platforms_keep_dot_arsync() {
+        assert_eq!(resolve_with(&[], true, true).cache, PathBuf::from("/home/u/.arsync/cache"));
+        assert_eq!(resolve_with(&[], false, false).store, PathBuf::from("/home/u/.arsync/store"));
+    }
+
+    #[test]
+    fn test_environment_overrides() {
+        let paths = resolve_with(&[("ARSYNC_HOME", "/opt/arsync"), ("ARSYNC_SHARED_CACHE", "/image/cache")], true, true);
+        assert_eq!(paths.cache, PathBuf::from("/opt/arsync/cache"));
+        assert_eq!(paths.store, PathBuf::from("/opt/arsync/store"));
+        assert_eq!(paths.shared_cache, Some(PathBuf::from("/image/cache")));
+        assert_eq!(paths.config, PathBuf::from("/opt/arsync/config.toml"));
+
+        let paths = resolve_with(&[("ARSYNC_CACHE_DIR", "/tmp/c")], false, true);
+        assert_eq!(paths.cache, PathBuf::from("/tmp/c"));
+        assert_eq!(paths.store, PathBuf::from("/home/u/.local/share/arsync/store"));
+    }
+}
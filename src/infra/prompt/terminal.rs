@@ -0,0 +1,54 @@
+use std::io::{self, BufRead, Write};
+use colored::Colorize;
+use crate::core::domain::error::AppError;
+use crate::core::port::prompter::Prompter;
+
+/// Prompts on stdout and reads answers from stdin.
+pub struct TerminalPrompter;
+
+impl TerminalPrompter {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn ask(&self, prompt: &str) -> Result<String, AppError> {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+
+        let mut answer = String::new();
+        if io::stdin().lock().read_line(&mut answer)? == 0 {
+            println!();
+            return Err(AppError::System("No input (stdin is closed); pass the values as flags or use --yes".to_string()));
+        }
+        Ok(answer.trim().to_string())
+    }
+}
+
+impl Default for TerminalPrompter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Prompter for TerminalPrompter {
+    fn input(&self, label: &str, default: Option<&str>) -> Result<String, AppError> {
+        let prompt = match default {
+            Some(default) => format!("{}: ({}) ", label, default.cyan()),
+            None => format!("{}: ", label),
+        };
+        let answer = self.ask(&prompt)?;
+        Ok(if answer.is_empty() { default.unwrap_or_default().to_string() } else { answer })
+    }
+
+    fn confirm(&self, label: &str, default: bool) -> Result<bool, AppError> {
+        let choices = if default { "Y/n" } else { "y/N" };
+        loop {
+            match self.ask(&format!("{} ({}) ", label, choices.cyan()))?.to_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => println!("Please answer y or n."),
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+use crate::core::domain::error::AppError;
+use crate::core::port::skill_store::SkillStore;
+use crate::infra::fs::local::remove_path;
+
+/// The content-addressed store, one folder per `<tree hash>` under `root`.
+pub struct LocalSkillStore {
+    root: PathBuf,
+}
+
+impl LocalSkillStore {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+impl SkillStore for LocalSkillStore {
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn contains(&self, integrity: &str) -> bool {
+        self.path(integrity).is_dir()
+    }
+
+    fn staging_dir(&self) -> Result<TempDir, AppError> {
+        fs::create_dir_all(&self.root).map_err(AppError::Io)?;
+        tempfile::Builder::new().prefix(".staging-").tempdir_in(&self.root)
+            .map_err(|e| AppError::System(format!("Failed to create temp dir: {}", e)))
+    }
+
+    fn add(&self, integrity: &str, staged: &Path) -> Result<(), AppError> {
+        let dest = self.path(integrity);
+        // Never remove an existing entry first: other projects may already link to it
+        match fs::rename(staged, &dest) {
+            Ok(()) => set_read_only(&dest)
+                .map_err(|e| AppError::System(format!("Failed to protect {}: {}", dest.display(), e))),
+            Err(_) if dest.is_dir() => remove_path(staged).map_err(AppError::Io),
+            Err(e) => Err(AppError::System(format!("Failed to add {} to the store: {}", dest.display(), e))),
+        }
+    }
+}
+
+/// Removes write permission from every file and directory under `path`, directories last.
+fn set_read_only(path: &Path) -> io::Result<()> {
+    let meta = fs::symlink_metadata(path)?;
+    if meta.file_type().is_symlink() {
+        return Ok(());
+    }
+    if meta.is_dir() {
+        for entry in fs::read_dir(path)? {
+            set_read_only(&entry?.path())?;
+        }
+    }
+    let mut permissions = meta.permissions();
+    permissions.set_readonly(true);
+    fs::set_permissions(path, permissions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_keeps_the_first_copy_and_protects_it() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = LocalSkillStore::new(tmp.path().join("store"));
+
+        let first = store.staging_dir().unwrap();
+        fs::write(first.path().join("SKILL.md"), "first").unwrap();
+        store.add("abc", first.path()).unwrap();
+        assert!(store.contains("abc"));
+        assert!(fs::metadata(store.path("abc").join("SKILL.md")).unwrap().permissions().readonly());
+
+        let second = store.staging_dir().unwrap();
+        let staged = second.path().join("skill");
+        fs::create_dir(&staged).unwrap();
+        fs::write(staged.join("SKILL.md"), "second").unwrap();
+        store.add("abc", &staged).unwrap();
+        assert!(!staged.exists());
+        assert_eq!(fs::read_to_string(store.path("abc").join("SKILL.md")).unwrap(), "first");
+
+        // Let the temp dir clean up
+        for path in [store.path("abc").join("SKILL.md"), store.path("abc")] {
+            let mut permissions = fs::metadata(&path).unwrap().permissions();
+            #[allow(clippy::permissions_set_readonly_false)]
+            permissions.set_readonly(false);
+            fs::set_permissions(&path, permissions).unwrap();
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::core::domain::copilot::to_copilot_instructions;
+use crate::core::domain::error::AppError;
+use crate::core::port::install_target::{InstallTarget, PlacedSkill};
+
+/// Copilot instructions: writes `<skill>.instructions.md` generated from `SKILL.md` next to the
+/// skill's folder, scoped by the dependency's `apply-to` glob.
+pub struct CopilotInstructionsTarget;
+
+impl InstallTarget for CopilotInstructionsTarget {
+    fn install(&self, skill: &PlacedSkill) -> Result<(), AppError> {
+        let skill_md = fs::read_to_string(skill.folder.join("SKILL.md"))
+            .map_err(|e| AppError::Skill(format!("Cannot generate Copilot instructions for {}: {}", skill.key, e)))?;
+        let resources = skill.folder.strip_prefix(skill.project_dir).unwrap_or(skill.folder).to_string_lossy().replace('\\', "/");
+        let instructions = to_copilot_instructions(&skill_md, skill.dependency.apply_to.as_deref(), &resources)?;
+        fs::write(instructions_path(skill.target_dir, skill.key), instructions).map_err(AppError::Io)
+    }
+
+    fn outputs(&self, target_dir: &Path, key: &str) -> Vec<PathBuf> {
+        vec![instructions_path(target_dir, key)]
+    }
+}
+
+fn instructions_path(target_dir: &Path, key: &str) -> PathBuf {
+    target_dir.join(format!("{}.instructions.md", key))
+}
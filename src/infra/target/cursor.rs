@@ -0,0 +1,26 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::core::domain::cursor::to_cursor_rule;
+use crate::core::domain::error::AppError;
+use crate::core::port::install_target::{InstallTarget, PlacedSkill};
+
+/// Cursor rules: writes `<skill>.mdc` generated from `SKILL.md` next to the skill's folder,
+/// which stays as the home of the files the rule refers to.
+pub struct CursorRulesTarget;
+
+impl InstallTarget for CursorRulesTarget {
+    fn install(&self, skill: &PlacedSkill) -> Result<(), AppError> {
+        let skill_md = fs::read_to_string(skill.folder.join("SKILL.md"))
+            .map_err(|e| AppError::Skill(format!("Cannot generate Cursor rule for {}: {}", skill.key, e)))?;
+        let resources = skill.folder.strip_prefix(skill.project_dir).unwrap_or(skill.folder).to_string_lossy().replace('\\', "/");
+        fs::write(rule_path(skill.target_dir, skill.key), to_cursor_rule(&skill_md, &resources)?).map_err(AppError::Io)
+    }
+
+    fn outputs(&self, target_dir: &Path, key: &str) -> Vec<PathBuf> {
+        vec![rule_path(target_dir, key)]
+    }
+}
+
+fn rule_path(target_dir: &Path, key: &str) -> PathBuf {
+    target_dir.join(format!("{}.mdc", key))
+}
@@ -0,0 +1,16 @@
+use std::path::{Path, PathBuf};
+use crate::core::domain::error::AppError;
+use crate::core::port::install_target::{InstallTarget, PlacedSkill};
+
+/// Targets whose agent reads skill folders directly; the placed folder is all it needs.
+pub struct DirectoryTarget;
+
+impl InstallTarget for DirectoryTarget {
+    fn install(&self, _skill: &PlacedSkill) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    fn outputs(&self, _target_dir: &Path, _key: &str) -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
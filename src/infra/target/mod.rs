@@ -0,0 +1,3 @@
+pub mod directory;
+pub mod cursor;
+pub mod copilot;
@@ -3,18 +3,42 @@ pub mod core;
 pub mod infra;
 
 use std::env;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use clap::Parser;
 use colored::Colorize;
-use cli::parser::{Cli, Commands};
+use cli::parser::{CacheCommands, Cli, Commands, ConfigCommands};
 use crate::infra::fs::local::LocalFileSystem;
+use crate::infra::fs::lock::FileLock;
 use crate::infra::manifest::file_repo::FileManifestRepository;
 use crate::infra::manifest::lockfile_repo::FileLockfileRepository;
 use crate::infra::git::fetcher::CliGitFetcher;
-use crate::core::usecase::init::InitUseCase;
+use crate::infra::git::cache::{self, now, GitRepositoryCache};
+use crate::infra::git::remote::{answer_askpass, NetworkPolicy};
+use crate::infra::store::local::LocalSkillStore;
+use crate::infra::paths::ArsyncPaths;
+use crate::infra::config::file_repo::TomlConfigRepository;
+use crate::infra::prompt::terminal::TerminalPrompter;
+use crate::infra::target::copilot::CopilotInstructionsTarget;
+use crate::infra::target::cursor::CursorRulesTarget;
+use crate::infra::target::directory::DirectoryTarget;
+use crate::core::usecase::init::{InitAnswers, InitUseCase};
 use crate::core::usecase::install::InstallUseCase;
 use crate::core::usecase::uninstall::UninstallUseCase;
 use crate::core::usecase::list::ListUseCase;
 use crate::core::usecase::update::UpdateUseCase;
+use crate::core::usecase::versions::VersionsUseCase;
+use crate::core::usecase::fetch::FetchUseCase;
+use crate::core::usecase::cache::CacheUseCase;
+use crate::core::usecase::catalog::CatalogUseCase;
+use crate::core::usecase::targets::TargetAdapters;
+use crate::core::domain::manifest::DEFAULT_TARGET_KIND;
+use crate::core::usecase::config::ConfigUseCase;
+use crate::core::port::repository_cache::RepositoryCache;
+use crate::core::domain::version::RefKind;
+use crate::core::domain::skill::{is_commit_hash, SourceRef};
+use crate::core::domain::config::{OutputFormat, Settings, DEFAULT_HOST, DEFAULT_INSTALL_DIR};
+use crate::core::domain::error::AppError;
 
 /// Resolves the install source string from a combination of shorthand positional argument and explicit flags.
 /// Returns `Ok(None)` for bare `arsync install` (environment restore), `Ok(Some(source))` for a resolved source,
@@ -90,22 +114,149 @@ fn resolve_install_source(
     }
 }
 
+/// Resolves the target ref for `update <skill> --to/--branch/--tag/--commit`.
+/// Returns `Ok(None)` when none of the flags were given (a regular update).
+fn resolve_update_ref(
+    to: &Option<String>,
+    branch: &Option<String>,
+    tag: &Option<String>,
+    commit: &Option<String>,
+) -> Result<Option<SourceRef>, String> {
+    if let Some(t) = to {
+        return SourceRef::parse(t).map(Some).map_err(|e| e.to_string());
+    }
+    if let Some(b) = branch {
+        return Ok(Some(SourceRef::Branch(b.clone())));
+    }
+    if let Some(t) = tag {
+        return Ok(Some(SourceRef::Tag(t.clone())));
+    }
+    if let Some(c) = commit {
+        if !is_commit_hash(c) {
+            return Err(format!("--commit expects a full commit hash, got '{}'", c));
+        }
+        return Ok(Some(SourceRef::Commit(c.clone())));
+    }
+    Ok(None)
+}
+
+/// Default concurrency for install/update: fetching is network-bound, so cap it
+/// rather than spawning one git process per core on large machines.
+fn default_jobs() -> usize {
+    std::thread::available_parallelism().map(|n| n.get()).unwrap_or(4).min(8)
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 { format!("{} B", bytes) } else { format!("{:.1} {}", value, UNITS[unit]) }
+}
+
+fn format_age(seconds: u64) -> String {
+    match seconds / (24 * 60 * 60) {
+        0 => "used today".to_string(),
+        1 => "used 1 day ago".to_string(),
+        days => format!("used {} days ago", days),
+    }
+}
+
+/// The built-in value used when no configuration layer sets `key`.
+fn default_setting(key: &str) -> String {
+    match key {
+        "install-dir" => DEFAULT_INSTALL_DIR.to_string(),
+        "host" => DEFAULT_HOST.to_string(),
+        "jobs" => default_jobs().to_string(),
+        "offline" | "strict" => "false".to_string(),
+        "output" => OutputFormat::Text.to_string(),
+        "timeout" => NetworkPolicy::DEFAULT_TIMEOUT_SECS.to_string(),
+        "retries" => NetworkPolicy::DEFAULT_RETRIES.to_string(),
+        _ => String::new(),
+    }
+}
+
+fn exit_on_error<T>(result: Result<T, AppError>) -> T {
+    result.unwrap_or_else(|e| {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    })
+}
+
+/// Keeps other arsync processes from changing the same project's manifest, lockfile and skills concurrently.
+/// Outside a project there is nothing to protect (the command fails on the missing manifest).
+fn lock_project(cache_root: &Path, dir: &Path) -> Option<FileLock> {
+    if !dir.join("skills.arsync").exists() {
+        return None;
+    }
+    Some(exit_on_error(cache::lock_project(cache_root, dir)))
+}
+
 fn main() {
+    // git runs this binary as its askpass helper to pick up access tokens
+    if answer_askpass() {
+        return;
+    }
+
     let cli = Cli::parse();
     
-    let current_dir = env::current_dir().unwrap_or_else(|_| std::path::PathBuf::from("."));
+    let current_dir = env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
     
     let fs = LocalFileSystem::new();
     let manifest_repo = FileManifestRepository::new();
     let lockfile_repo = FileLockfileRepository::new();
-    let git_fetcher = CliGitFetcher::new();
+    let paths = exit_on_error(ArsyncPaths::from_env());
+
+    // Command-line flags win over the configuration layers
+    let config_repo = TomlConfigRepository::new();
+    let project_config = current_dir.join(".arsync.toml");
+    let env_settings = exit_on_error(Settings::from_env(|name| env::var(name).ok()));
+    let config = ConfigUseCase::new(&config_repo, &paths.config, &project_config, env_settings);
+    let settings = exit_on_error(config.effective());
+    let offline = cli.offline || settings.offline.unwrap_or(false);
+    let strict = cli.strict || settings.strict.unwrap_or(false);
+    let output = cli.output.or(settings.output).unwrap_or_default();
+    let jobs_or_default = |jobs: &Option<usize>| jobs.or(settings.jobs).unwrap_or_else(default_jobs);
+    let network = NetworkPolicy {
+        timeout: match settings.timeout {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => NetworkPolicy::default().timeout,
+        },
+        retries: settings.retries.unwrap_or(NetworkPolicy::DEFAULT_RETRIES),
+    };
+
+    let git_fetcher = CliGitFetcher::new(paths.cache.clone())
+        .with_shared_cache(paths.shared_cache.clone())
+        .with_host(settings.host.clone().unwrap_or_else(|| DEFAULT_HOST.to_string()))
+        .with_tokens(settings.tokens.clone())
+        .with_url_rules(settings.url_rules())
+        .with_network_policy(network)
+        .with_offline(offline);
     
+    // Install target kinds usable in the manifest
+    let adapters = TargetAdapters::new()
+        .register(DEFAULT_TARGET_KIND, &DirectoryTarget)
+        .register("cursor", &CursorRulesTarget)
+        .register("copilot", &CopilotInstructionsTarget);
+
     let result = match &cli.command {
-        Commands::Init => {
-            let usecase = InitUseCase::new(&manifest_repo, &lockfile_repo, &fs);
+        Commands::Init { name, description, install_dir, yes } => {
+            let prompter = TerminalPrompter::new();
+            let usecase = InitUseCase::new(&manifest_repo, &lockfile_repo, &fs, &prompter)
+                .with_default_install_dir(settings.install_dir.clone().unwrap_or_else(|| DEFAULT_INSTALL_DIR.to_string()))
+                .with_answers(InitAnswers {
+                    name: name.clone(),
+                    description: description.clone(),
+                    install_dir: install_dir.clone(),
+                    yes: *yes,
+                });
             usecase.execute(&current_dir)
         },
-        Commands::Install { source, owner, repository, branch, tag, path } => {
+        Commands::Install { source, owner, repository, branch, tag, path, targets, jobs } => {
             let final_source = match resolve_install_source(source, owner, repository, branch, tag, path) {
                 Ok(s) => s,
                 Err(msg) => {
@@ -114,16 +265,31 @@ fn main() {
                 }
             };
 
-            let usecase = InstallUseCase::new(&manifest_repo, &git_fetcher, &lockfile_repo);
+            let _lock = lock_project(&paths.cache, &current_dir);
+            let store = LocalSkillStore::new(paths.store.clone());
+            let usecase = InstallUseCase::new(&manifest_repo, &git_fetcher, &lockfile_repo, &fs, &store, &adapters)
+                .with_jobs(jobs_or_default(jobs))
+                .with_strict(strict)
+                .with_targets(targets.clone());
             usecase.execute(&current_dir, final_source)
+                .and_then(|()| CatalogUseCase::new(&manifest_repo, &fs).refresh(&current_dir))
         },
         Commands::Uninstall { skill_name } => {
-            let usecase = UninstallUseCase::new(&manifest_repo, &fs, &lockfile_repo);
+            let _lock = lock_project(&paths.cache, &current_dir);
+            let usecase = UninstallUseCase::new(&manifest_repo, &fs, &lockfile_repo, &adapters);
             usecase.execute(&current_dir, skill_name)
+                .and_then(|()| CatalogUseCase::new(&manifest_repo, &fs).refresh(&current_dir))
         },
         Commands::List => {
             let usecase = ListUseCase::new(&manifest_repo);
             match usecase.execute(&current_dir) {
+                Ok(skills) if output == OutputFormat::Json => {
+                    let skills: Vec<_> = skills.iter()
+                        .map(|(name, source)| serde_json::json!({ "name": name, "source": source }))
+                        .collect();
+                    println!("{}", serde_json::Value::Array(skills));
+                    Ok(())
+                },
                 Ok(skills) => {
                     if skills.is_empty() {
                         println!("No skills installed.");
@@ -138,10 +304,166 @@ fn main() {
                 Err(e) => Err(e),
             }
         },
-        Commands::Update { skill_name } => {
-            let usecase = UpdateUseCase::new(&manifest_repo, &git_fetcher, &lockfile_repo);
-            usecase.execute(&current_dir, skill_name.as_deref())
+        Commands::Update { skill_name, to, branch, tag, commit, jobs, keep_unchanged } => {
+            let new_ref = match resolve_update_ref(to, branch, tag, commit) {
+                Ok(r) => r,
+                Err(msg) => {
+                    eprintln!("{}", msg.red().bold());
+                    std::process::exit(1);
+                }
+            };
+
+            let _lock = lock_project(&paths.cache, &current_dir);
+            let store = LocalSkillStore::new(paths.store.clone());
+            let usecase = UpdateUseCase::new(&manifest_repo, &git_fetcher, &lockfile_repo, &fs, &store, &adapters)
+                .with_jobs(jobs_or_default(jobs))
+                .with_keep_unchanged(*keep_unchanged)
+                .with_strict(strict);
+            match (skill_name, new_ref) {
+                (Some(key), Some(r)) => usecase.retarget(&current_dir, key, &r),
+                _ => usecase.execute(&current_dir, skill_name.as_deref()),
+            }.and_then(|()| CatalogUseCase::new(&manifest_repo, &fs).refresh(&current_dir))
+        }
+        Commands::Fetch { paths, jobs } => {
+            // A skills.arsync file stands for the project directory that contains it; any other
+            // file is refused rather than swapped for the skills.arsync next to it
+            let dirs: Result<Vec<PathBuf>, AppError> = if paths.is_empty() {
+                Ok(vec![current_dir.clone()])
+            } else {
+                paths.iter().map(|p| {
+                    if !p.is_file() {
+                        Ok(p.clone())
+                    } else if p.file_name().is_some_and(|n| n == "skills.arsync") {
+                        Ok(p.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or(Path::new(".")).to_path_buf())
+                    } else {
+                        Err(AppError::Manifest(format!("'{}' is not a skills.arsync file; pass the project directory or its skills.arsync", p.display())))
+                    }
+                }).collect()
+            };
+            dirs.and_then(|dirs| {
+                let dirs: Vec<&Path> = dirs.iter().map(|d| d.as_path()).collect();
+                let usecase = FetchUseCase::new(&manifest_repo, &git_fetcher, &lockfile_repo)
+                    .with_jobs(jobs_or_default(jobs));
+                usecase.execute(&dirs)
+            })
+        }
+        Commands::Versions { target } => {
+            let usecase = VersionsUseCase::new(&manifest_repo, &git_fetcher, &lockfile_repo);
+            usecase.execute(&current_dir, target).map(|report| {
+                if output == OutputFormat::Json {
+                    let refs: Vec<_> = report.refs.iter().map(|r| serde_json::json!({
+                        "name": r.name,
+                        "kind": if r.kind == RefKind::Tag { "tag" } else { "branch" },
+                        "commit": r.commit,
+                        "date": r.date,
+                    })).collect();
+                    println!("{}", serde_json::json!({ "source": report.source.as_str(), "locked": report.locked_commit, "refs": refs }));
+                    return;
+                }
+                println!("Versions of {}:", report.source.as_str());
+                for (kind, title) in [(RefKind::Tag, "Tags"), (RefKind::Branch, "Branches")] {
+                    let refs: Vec<_> = report.refs.iter().filter(|r| r.kind == kind).collect();
+                    if refs.is_empty() {
+                        continue;
+                    }
+                    println!("  {}:", title);
+                    let width = refs.iter().map(|r| r.name.len()).max().unwrap_or(0);
+                    for r in refs {
+                        let locked = if report.locked_commit.as_deref() == Some(r.commit.as_str()) {
+                            format!("  {}", "(locked)".green().bold())
+                        } else {
+                            String::new()
+                        };
+                        println!(
+                            "    {:width$}  {}  {}{}",
+                            r.name,
+                            &r.commit[..8.min(r.commit.len())],
+                            r.date.as_deref().unwrap_or("----------"),
+                            locked,
+                            width = width
+                        );
+                    }
+                }
+                if let Some(locked) = &report.locked_commit {
+                    if !report.refs.iter().any(|r| &r.commit == locked) {
+                        println!("  Locked: {} (not at any branch or tag)", &locked[..8.min(locked.len())]);
+                    }
+                }
+                // ls-remote does not advertise dates; they are read from the local cache
+                if report.refs.iter().any(|r| r.date.is_none()) {
+                    println!("  {} Dates are shown once the repository is in the local cache (`arsync fetch`).", "ℹ".blue());
+                }
+            })
+        },
+        Commands::Catalog { files } => {
+            let _lock = lock_project(&paths.cache, &current_dir);
+            CatalogUseCase::new(&manifest_repo, &fs).execute(&current_dir, files)
+        },
+        Commands::Cache { command } => {
+            let cache = GitRepositoryCache::new(paths.cache.clone());
+            let usecase = CacheUseCase::new(&cache);
+            match command {
+                CacheCommands::Dir => {
+                    println!("{}", cache.root().display());
+                    Ok(())
+                }
+                CacheCommands::List => usecase.list().map(|repos| {
+                    if output == OutputFormat::Json {
+                        let repos: Vec<_> = repos.iter()
+                            .map(|r| serde_json::json!({ "repository": r.repository, "size": r.size, "last-used": r.last_used }))
+                            .collect();
+                        println!("{}", serde_json::Value::Array(repos));
+                        return;
+                    }
+                    if repos.is_empty() {
+                        println!("The cache is empty.");
+                        return;
+                    }
+                    let width = repos.iter().map(|r| r.repository.len()).max().unwrap_or(0);
+                    for repo in &repos {
+                        println!("{:width$}  {:>9}  {}", repo.repository, format_size(repo.size), format_age(now().saturating_sub(repo.last_used)), width = width);
+                    }
+                    println!("Total: {}", format_size(repos.iter().map(|r| r.size).sum()));
+                }),
+                CacheCommands::Clean { repository } => usecase.clean(repository.as_deref()),
+                CacheCommands::Verify { repository } => usecase.verify(repository.as_deref()),
+                CacheCommands::Gc { older_than, max_size } => {
+                    usecase.gc(older_than.map(|days| days * 24 * 60 * 60), max_size.map(|mb| mb * 1024 * 1024))
+                }
+            }
         }
+        Commands::Config { command } => match command {
+            ConfigCommands::Get { key } => config.get(key).map(|(value, _)| {
+                println!("{}", value.unwrap_or_else(|| default_setting(key)));
+            }),
+            ConfigCommands::Set { key, value, project } => config.set(key, value, *project).map(|_| {
+                let path = if *project { &project_config } else { &paths.config };
+                println!("{} Set {} in {}", "✔".green().bold(), key, path.display());
+            }),
+            ConfigCommands::Unset { key, project } => config.unset(key, *project).map(|_| {
+                let path = if *project { &project_config } else { &paths.config };
+                println!("{} Removed {} from {}", "✔".green().bold(), key, path.display());
+            }),
+            ConfigCommands::List => config.list().map(|entries| {
+                let entries: Vec<_> = entries.into_iter()
+                    .map(|entry| {
+                        let value = entry.value.unwrap_or_else(|| default_setting(&entry.key));
+                        (entry.key, value, entry.source)
+                    })
+                    .collect();
+                if output == OutputFormat::Json {
+                    let entries: Vec<_> = entries.iter()
+                        .map(|(key, value, source)| serde_json::json!({ "key": key, "value": value, "source": source.as_str() }))
+                        .collect();
+                    println!("{}", serde_json::Value::Array(entries));
+                    return;
+                }
+                let width = entries.iter().map(|(key, _, _)| key.len()).max().unwrap_or(0);
+                for (key, value, source) in &entries {
+                    println!("{:width$} = {}  {}", key, value, format!("({})", source.as_str()).dimmed(), width = width);
+                }
+            }),
+        },
     };
     
     if let Err(e) = result {
@@ -200,5 +522,35 @@ mod tests {
         );
         assert_eq!(result.unwrap(), Some("owner/repo#main".to_string()));
     }
-}
 
+    #[test]
+    fn test_resolve_update_ref_variants() {
+        let sha = "0123456789abcdef0123456789abcdef01234567".to_string();
+        assert_eq!(resolve_update_ref(&None, &None, &None, &None).unwrap(), None);
+        assert_eq!(
+            resolve_update_ref(&Some("@v2.0.0".to_string()), &None, &None, &None).unwrap(),
+            Some(SourceRef::Tag("v2.0.0".to_string()))
+        );
+        assert_eq!(
+            resolve_update_ref(&None, &Some("main".to_string()), &None, &None).unwrap(),
+            Some(SourceRef::Branch("main".to_string()))
+        );
+        assert_eq!(
+            resolve_update_ref(&None, &None, &None, &Some(sha.clone())).unwrap(),
+            Some(SourceRef::Commit(sha))
+        );
+    }
+
+    #[test]
+    fn test_resolve_update_ref_rejects_short_commit() {
+        let result = resolve_update_ref(&None, &None, &None, &Some("abc123".to_string()));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}